@@ -1,10 +1,45 @@
 use crate::game::*;
+use crate::heuristic::Heuristic;
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
+use std::time::Duration;
+use std::time::Instant;
+
+// what the last search found, score is in the agent's own units, higher is better for player true
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SearchInfo<M> {
+	pub depth: u32,
+	pub score: i64,
+	pub nodes: u64,
+	pub pv: Vec<M>,
+}
 
 pub trait Ai<G: Game> {
-	fn new(t: bool) -> Self;
+	fn new(t: bool) -> Self
+	where
+		Self: Sized;
 	fn state(&self) -> State;
 	fn print2game(&self);
 	fn turn(&self) -> bool;
 	fn get_mov(&mut self, tl: std::time::Duration) -> G::M;
 	fn mov(&mut self, m: &G::M);
+	// about the last get_mov, for agents that keep track of it
+	fn info(&self) -> Option<SearchInfo<G::M>> {
+		None
+	}
+	// once stop is set get_mov should return as soon as it can
+	fn set_stop(&mut self, _stop: Arc<AtomicBool>) {}
+}
+
+// the solved move and its value if H can solve g in half of tl, else the time left of tl
+// for searching, the clock starts before solving
+pub fn solve_or_time_left<G: Game, H: Heuristic<G>>(
+	g: &G,
+	tl: Duration,
+) -> Result<(G::M, i64), Duration> {
+	let st = Instant::now();
+	match H::solve(g, st + tl / 2) {
+		Some(x) => Ok(x),
+		None => Err(tl.saturating_sub(st.elapsed())),
+	}
 }
@@ -0,0 +1,198 @@
+// Game of the Amazons on 10x10: each player has four amazons, a move takes one like
+// a chess queen and then shoots an arrow from where it lands, also like a queen.
+// Arrows stay for good, the player who can't move loses.
+// Square (x, y) is y * 10 + x, a set of squares is a u128.
+use crate::game::*;
+
+pub const SIZE: usize = 10;
+const DIRS: [(i32, i32); 8] = [
+	(1, 0),
+	(-1, 0),
+	(0, 1),
+	(0, -1),
+	(1, 1),
+	(1, -1),
+	(-1, 1),
+	(-1, -1),
+];
+
+// squares reached like a queen from p through the empty squares
+pub fn queen_moves(p: u8, occupied: u128) -> u128 {
+	let (x, y) = ((p as usize % SIZE) as i32, (p as usize / SIZE) as i32);
+	let mut ans = 0;
+	for (dx, dy) in DIRS {
+		let (mut qx, mut qy) = (x + dx, y + dy);
+		while qx >= 0 && qy >= 0 && qx < SIZE as i32 && qy < SIZE as i32 {
+			let q = 1u128 << (qy as usize * SIZE + qx as usize);
+			if occupied & q != 0 {
+				break;
+			}
+			ans |= q;
+			qx += dx;
+			qy += dy;
+		}
+	}
+	ans
+}
+
+pub fn squares(mut b: u128) -> impl Iterator<Item = u8> {
+	std::iter::from_fn(move || {
+		if b == 0 {
+			return None;
+		}
+		let p = b.trailing_zeros() as u8;
+		b &= b - 1;
+		Some(p)
+	})
+}
+
+#[derive(Debug, Hash, Clone, Copy, PartialEq, Eq)]
+pub struct Amazons {
+	// amazons of player true and of player false
+	pub amazons: (u128, u128),
+	pub arrows: u128,
+	turn: bool,
+}
+
+impl Amazons {
+	pub fn occupied(&self) -> u128 {
+		self.amazons.0 | self.amazons.1 | self.arrows
+	}
+	fn own(&self) -> u128 {
+		if self.turn {
+			self.amazons.0
+		} else {
+			self.amazons.1
+		}
+	}
+	// an amazon next to an empty square can step there and shoot back
+	fn can_move(&self) -> bool {
+		let occupied = self.occupied();
+		squares(self.own()).any(|p| {
+			let (x, y) = ((p as usize % SIZE) as i32, (p as usize / SIZE) as i32);
+			DIRS.iter().any(|(dx, dy)| {
+				let (qx, qy) = (x + dx, y + dy);
+				qx >= 0
+					&& qy >= 0
+					&& qx < SIZE as i32
+					&& qy < SIZE as i32
+					&& occupied & (1u128 << (qy as usize * SIZE + qx as usize)) == 0
+			})
+		})
+	}
+}
+
+impl Game for Amazons {
+	type M = (u8, u8, u8); // from, to, arrow
+	type S = (u128, u128, u128, bool);
+	type R = (u8, u8, u8);
+	fn new(t: bool) -> Self {
+		// a4 d1 g1 j4 and a7 d10 g10 j7
+		let sq = |x: usize, y: usize| 1u128 << (y * SIZE + x);
+		Amazons {
+			amazons: (
+				sq(0, 3) | sq(3, 0) | sq(6, 0) | sq(9, 3),
+				sq(0, 6) | sq(3, 9) | sq(6, 9) | sq(9, 6),
+			),
+			arrows: 0,
+			turn: t,
+		}
+	}
+	fn turn(&self) -> bool {
+		self.turn
+	}
+	fn get_moves(&self) -> Vec<(u8, u8, u8)> {
+		let occupied = self.occupied();
+		let mut ans = vec![];
+		for from in squares(self.own()) {
+			let without = occupied & !(1u128 << from);
+			for to in squares(queen_moves(from, occupied)) {
+				for arrow in squares(queen_moves(to, without)) {
+					ans.push((from, to, arrow));
+				}
+			}
+		}
+		ans
+	}
+	fn get_moves_sorted(&self) -> Vec<(u8, u8, u8)> {
+		// arrows close to the other player's amazons first
+		let opp = if self.turn {
+			self.amazons.1
+		} else {
+			self.amazons.0
+		};
+		let opp: Vec<(i32, i32)> = squares(opp)
+			.map(|p| ((p as usize % SIZE) as i32, (p as usize / SIZE) as i32))
+			.collect();
+		let mut ans = self.get_moves();
+		ans.sort_by_cached_key(|m| {
+			let (x, y) = ((m.2 as usize % SIZE) as i32, (m.2 as usize / SIZE) as i32);
+			opp
+				.iter()
+				.map(|(ox, oy)| (x - ox).abs().max((y - oy).abs()))
+				.min()
+		});
+		ans
+	}
+	fn get_static_state(&self) -> Self::S {
+		(self.amazons.0, self.amazons.1, self.arrows, self.turn)
+	}
+	fn state(&self) -> State {
+		if self.can_move() {
+			State::Going
+		} else if self.turn {
+			State::Lose
+		} else {
+			State::Win
+		}
+	}
+	fn mov(&mut self, m: &(u8, u8, u8)) {
+		let own = if self.turn {
+			&mut self.amazons.0
+		} else {
+			&mut self.amazons.1
+		};
+		*own ^= (1u128 << m.0) | (1u128 << m.1);
+		self.arrows |= 1u128 << m.2;
+		self.turn = !self.turn;
+	}
+	fn mov_with_rollback(&mut self, m: &(u8, u8, u8)) -> Self::R {
+		self.mov(m);
+		*m
+	}
+	fn rollback(&mut self, m: Self::R) {
+		self.turn = !self.turn;
+		self.arrows &= !(1u128 << m.2);
+		let own = if self.turn {
+			&mut self.amazons.0
+		} else {
+			&mut self.amazons.1
+		};
+		*own ^= (1u128 << m.0) | (1u128 << m.1);
+	}
+}
+
+impl std::fmt::Display for Amazons {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		for y in (0..SIZE).rev() {
+			for x in 0..SIZE {
+				let p = 1u128 << (y * SIZE + x);
+				write!(
+					f,
+					"{}",
+					if self.amazons.0 & p != 0 {
+						'X'
+					} else if self.amazons.1 & p != 0 {
+						'O'
+					} else if self.arrows & p != 0 {
+						'#'
+					} else {
+						'.'
+					}
+				)?;
+			}
+			writeln!(f)?;
+		}
+		Ok(())
+	}
+}
@@ -0,0 +1,152 @@
+// Breakthrough on 8x8: two rows of pawns each, a pawn steps one square forward
+// or diagonally forward, takes only diagonally. Reaching the far row wins, so does
+// leaving the other player without pawns or moves.
+// Player true starts on rows 1-2 and goes up, square (x, y) is y * 8 + x.
+use crate::game::*;
+
+const NOT_A: u64 = 0xfefefefefefefefe;
+const NOT_H: u64 = 0x7f7f7f7f7f7f7f7f;
+pub const ROW_1: u64 = 0xff;
+pub const ROW_8: u64 = 0xff << 56;
+
+#[derive(Debug, Hash, Clone, Copy, PartialEq, Eq)]
+pub struct Breakthrough {
+	// pawns of player true and of player false
+	pub board: (u64, u64),
+	turn: bool,
+}
+
+// squares the pawns of own reach, straight, to the left and to the right, with the shifts
+fn targets(own: u64, opp: u64, up: bool) -> [(u64, i32); 3] {
+	let empty = !(own | opp);
+	if up {
+		[
+			((own << 8) & empty, 8),
+			(((own & NOT_A) << 7) & !own, 7),
+			(((own & NOT_H) << 9) & !own, 9),
+		]
+	} else {
+		[
+			((own >> 8) & empty, -8),
+			(((own & NOT_A) >> 9) & !own, -9),
+			(((own & NOT_H) >> 7) & !own, -7),
+		]
+	}
+}
+
+impl Breakthrough {
+	// the player to move's pawns first
+	fn own_opp(&self) -> (u64, u64) {
+		if self.turn {
+			self.board
+		} else {
+			(self.board.1, self.board.0)
+		}
+	}
+	fn can_move(&self, t: bool) -> bool {
+		let (own, opp) = if t {
+			self.board
+		} else {
+			(self.board.1, self.board.0)
+		};
+		targets(own, opp, t).iter().any(|x| x.0 != 0)
+	}
+}
+
+impl Game for Breakthrough {
+	type M = (u8, u8);
+	type S = (u64, u64, bool);
+	type R = (u64, u64);
+	fn new(t: bool) -> Self {
+		Breakthrough {
+			board: (0xffff, 0xffff << 48),
+			turn: t,
+		}
+	}
+	fn turn(&self) -> bool {
+		self.turn
+	}
+	fn get_moves(&self) -> Vec<(u8, u8)> {
+		let (own, opp) = self.own_opp();
+		let mut ans = Vec::with_capacity(48);
+		for (mut b, s) in targets(own, opp, self.turn) {
+			while b != 0 {
+				let to = b.trailing_zeros() as i32;
+				b &= b - 1;
+				ans.push(((to - s) as u8, to as u8));
+			}
+		}
+		ans
+	}
+	fn get_moves_sorted(&self) -> Vec<(u8, u8)> {
+		// captures first, then the pawns furthest ahead
+		let (_, opp) = self.own_opp();
+		let mut ans = self.get_moves();
+		ans.sort_by_key(|m| {
+			let ahead = if self.turn { m.1 / 8 } else { 7 - m.1 / 8 };
+			(((opp >> m.1) & 1) == 0, 7 - ahead)
+		});
+		ans
+	}
+	fn get_static_state(&self) -> Self::S {
+		(self.board.0, self.board.1, self.turn)
+	}
+	fn state(&self) -> State {
+		if self.board.0 & ROW_8 != 0 || self.board.1 == 0 {
+			State::Win
+		} else if self.board.1 & ROW_1 != 0 || self.board.0 == 0 {
+			State::Lose
+		} else if !self.can_move(self.turn) {
+			if self.turn {
+				State::Lose
+			} else {
+				State::Win
+			}
+		} else {
+			State::Going
+		}
+	}
+	fn mov(&mut self, m: &(u8, u8)) {
+		let (from, to) = (1u64 << m.0, 1u64 << m.1);
+		let (own, opp) = if self.turn {
+			(&mut self.board.0, &mut self.board.1)
+		} else {
+			(&mut self.board.1, &mut self.board.0)
+		};
+		*own ^= from | to;
+		*opp &= !to;
+		self.turn = !self.turn;
+	}
+	fn mov_with_rollback(&mut self, m: &(u8, u8)) -> Self::R {
+		let rb = self.board;
+		self.mov(m);
+		rb
+	}
+	fn rollback(&mut self, rb: Self::R) {
+		self.board = rb;
+		self.turn = !self.turn;
+	}
+}
+
+impl std::fmt::Display for Breakthrough {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		for y in (0..8).rev() {
+			for x in 0..8 {
+				let p = 1u64 << (y * 8 + x);
+				write!(
+					f,
+					"{}",
+					if self.board.0 & p != 0 {
+						'X'
+					} else if self.board.1 & p != 0 {
+						'O'
+					} else {
+						'.'
+					}
+				)?;
+			}
+			writeln!(f)?;
+		}
+		Ok(())
+	}
+}
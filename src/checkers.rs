@@ -0,0 +1,238 @@
+// English draughts: men move one square diagonally forward, kings either way, and
+// a piece jumps an adjacent enemy piece onto the empty square behind it. Capturing
+// is compulsory and a jump goes on while it can, the whole chain is one move.
+// A man reaching the far row becomes a king, which ends the move. The player who
+// can't move loses, QUIET_MOVES moves of each player without a capture or a man
+// moving is a draw.
+// Squares are numbered 0 to 31 row by row, four dark squares per row, the notation
+// adds one. Player true starts on 0-11 and moves to higher rows.
+use crate::game::*;
+
+pub const QUIET_MOVES: u8 = 40;
+const DIRS: [(i32, i32); 4] = [(1, 1), (-1, 1), (1, -1), (-1, -1)];
+
+pub fn xy(p: u8) -> (i32, i32) {
+	let y = (p / 4) as i32;
+	(2 * (p % 4) as i32 + 1 - y % 2, y)
+}
+
+pub fn square(x: i32, y: i32) -> Option<u8> {
+	if x < 0 || y < 0 || x >= 8 || y >= 8 || (x + y) % 2 == 0 {
+		None
+	} else {
+		Some((y * 4 + x / 2) as u8)
+	}
+}
+
+// the square jumped going from a to b
+pub fn jumped(a: u8, b: u8) -> Option<u8> {
+	let ((ax, ay), (bx, by)) = (xy(a), xy(b));
+	if (ax - bx).abs() != 2 || (ay - by).abs() != 2 {
+		return None;
+	}
+	square((ax + bx) / 2, (ay + by) / 2)
+}
+
+#[derive(Debug, Hash, Clone, Copy, PartialEq, Eq)]
+pub struct Checkers {
+	// pieces of player true and of player false
+	pub pieces: (u32, u32),
+	pub kings: u32,
+	turn: bool,
+	// plies without a capture or a man moving
+	quiet: u8,
+}
+
+impl Checkers {
+	fn own_opp(&self) -> (u32, u32) {
+		if self.turn {
+			self.pieces
+		} else {
+			(self.pieces.1, self.pieces.0)
+		}
+	}
+	// directions the piece on p can move in
+	fn dirs(&self, p: u8) -> &'static [(i32, i32)] {
+		if self.kings & (1 << p) != 0 {
+			&DIRS
+		} else if self.turn {
+			&DIRS[..2]
+		} else {
+			&DIRS[2..]
+		}
+	}
+	fn crown_row(&self) -> u8 {
+		if self.turn {
+			7
+		} else {
+			0
+		}
+	}
+	// a man moving from from to to becomes a king
+	fn crowns(&self, from: u8, to: u8) -> bool {
+		self.kings & (1 << from) == 0 && to / 4 == self.crown_row()
+	}
+	// the jump chains of the piece that started on from and is on p
+	fn jumps(&self, from: u8, p: u8, captured: u32, ans: &mut Vec<(u8, u8, u32)>) {
+		let (own, opp) = self.own_opp();
+		let empty = !((own & !(1 << from)) | opp);
+		let (x, y) = xy(p);
+		let mut more = false;
+		// crowning ends the move
+		if !self.crowns(from, p) {
+			for (dx, dy) in self.dirs(from) {
+				if let (Some(mid), Some(to)) = (square(x + dx, y + dy), square(x + 2 * dx, y + 2 * dy)) {
+					if opp & !captured & (1 << mid) != 0 && empty & (1 << to) != 0 {
+						more = true;
+						self.jumps(from, to, captured | (1 << mid), ans);
+					}
+				}
+			}
+		}
+		if !more && captured != 0 {
+			ans.push((from, p, captured));
+		}
+	}
+	fn captures(&self) -> Vec<(u8, u8, u32)> {
+		let mut ans = vec![];
+		let mut own = self.own_opp().0;
+		while own != 0 {
+			let p = own.trailing_zeros() as u8;
+			own &= own - 1;
+			self.jumps(p, p, 0, &mut ans);
+		}
+		// a king can go around the same pieces both ways
+		ans.sort_unstable();
+		ans.dedup();
+		ans
+	}
+	fn steps(&self) -> Vec<(u8, u8, u32)> {
+		let (own, opp) = self.own_opp();
+		let mut ans = vec![];
+		let mut b = own;
+		while b != 0 {
+			let p = b.trailing_zeros() as u8;
+			b &= b - 1;
+			let (x, y) = xy(p);
+			for (dx, dy) in self.dirs(p) {
+				if let Some(to) = square(x + dx, y + dy) {
+					if (own | opp) & (1 << to) == 0 {
+						ans.push((p, to, 0));
+					}
+				}
+			}
+		}
+		ans
+	}
+	pub fn quiet(&self) -> u8 {
+		self.quiet
+	}
+}
+
+impl Game for Checkers {
+	type M = (u8, u8, u32); // from, to and the pieces taken
+	type S = (u32, u32, u32, bool);
+	type R = ((u32, u32), u32, u8);
+	fn new(t: bool) -> Self {
+		Checkers {
+			pieces: (0xfff, 0xfff << 20),
+			kings: 0,
+			turn: t,
+			quiet: 0,
+		}
+	}
+	fn turn(&self) -> bool {
+		self.turn
+	}
+	fn get_moves(&self) -> Vec<(u8, u8, u32)> {
+		let ans = self.captures();
+		if ans.is_empty() {
+			self.steps()
+		} else {
+			ans
+		}
+	}
+	fn get_moves_sorted(&self) -> Vec<(u8, u8, u32)> {
+		// the longest chains first, then crowning
+		let mut ans = self.get_moves();
+		ans.sort_by_key(|m| (std::cmp::Reverse(m.2.count_ones()), !self.crowns(m.0, m.1)));
+		ans
+	}
+	fn get_static_state(&self) -> Self::S {
+		(self.pieces.0, self.pieces.1, self.kings, self.turn)
+	}
+	fn state(&self) -> State {
+		if self.quiet >= 2 * QUIET_MOVES {
+			State::Draw
+		} else if !self.get_moves().is_empty() {
+			State::Going
+		} else if self.turn {
+			State::Lose
+		} else {
+			State::Win
+		}
+	}
+	fn mov(&mut self, m: &(u8, u8, u32)) {
+		let (from, to) = (1u32 << m.0, 1u32 << m.1);
+		let man = self.kings & from == 0;
+		let crowned = self.crowns(m.0, m.1);
+		let (own, opp) = if self.turn {
+			(&mut self.pieces.0, &mut self.pieces.1)
+		} else {
+			(&mut self.pieces.1, &mut self.pieces.0)
+		};
+		*own ^= from | to;
+		*opp &= !m.2;
+		self.kings &= !m.2;
+		if !man {
+			self.kings ^= from | to;
+		} else if crowned {
+			self.kings |= to;
+		}
+		self.quiet = if man || m.2 != 0 { 0 } else { self.quiet + 1 };
+		self.turn = !self.turn;
+	}
+	fn mov_with_rollback(&mut self, m: &(u8, u8, u32)) -> Self::R {
+		let rb = (self.pieces, self.kings, self.quiet);
+		self.mov(m);
+		rb
+	}
+	fn rollback(&mut self, (pieces, kings, quiet): Self::R) {
+		(self.pieces, self.kings, self.quiet) = (pieces, kings, quiet);
+		self.turn = !self.turn;
+	}
+}
+
+impl std::fmt::Display for Checkers {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		for y in (0..8).rev() {
+			for x in 0..8 {
+				let c = match square(x, y) {
+					None => ' ',
+					Some(p) => {
+						let b = 1u32 << p;
+						let king = self.kings & b != 0;
+						if self.pieces.0 & b != 0 {
+							if king {
+								'X'
+							} else {
+								'x'
+							}
+						} else if self.pieces.1 & b != 0 {
+							if king {
+								'O'
+							} else {
+								'o'
+							}
+						} else {
+							'.'
+						}
+					}
+				};
+				write!(f, "{}", c)?;
+			}
+			writeln!(f)?;
+		}
+		Ok(())
+	}
+}
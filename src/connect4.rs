@@ -0,0 +1,159 @@
+use crate::game::*;
+
+// a column is 7 bits, 6 cells from the bottom up and a padding bit
+// so that shifts never carry a line over to the next column
+pub const WIDTH: u8 = 7;
+pub const HEIGHT: u8 = 6;
+const H1: u8 = HEIGHT + 1;
+pub const BOTTOM: u64 = 0b0000001_0000001_0000001_0000001_0000001_0000001_0000001;
+pub const FULL: u64 = BOTTOM * ((1 << HEIGHT) - 1);
+const ORDER: [u8; 7] = [3, 2, 4, 1, 5, 0, 6];
+
+pub fn column(c: u8) -> u64 {
+	((1u64 << HEIGHT) - 1) << (c * H1)
+}
+fn top(c: u8) -> u64 {
+	1u64 << (HEIGHT - 1 + c * H1)
+}
+
+// four in a line somewhere in b
+pub fn aligned(b: u64) -> bool {
+	for d in [1, H1 - 1, H1, H1 + 1] {
+		let m = b & (b >> d);
+		if m & (m >> (2 * d)) != 0 {
+			return true;
+		}
+	}
+	false
+}
+
+// empty cells that would make four in a line for b
+pub fn threats(b: u64, mask: u64) -> u64 {
+	// vertical
+	let mut r = (b << 1) & (b << 2) & (b << 3);
+	for d in [H1 - 1, H1, H1 + 1] {
+		let p = (b << d) & (b << (2 * d));
+		r |= p & (b << (3 * d));
+		r |= p & (b >> d);
+		let p = (b >> d) & (b >> (2 * d));
+		r |= p & (b << d);
+		r |= p & (b >> (3 * d));
+	}
+	r & FULL & !mask
+}
+
+#[derive(Debug, Hash, Clone, Copy, PartialEq, Eq)]
+pub struct Connect4 {
+	pub board: (u64, u64), // .0: stones of player true, .1: stones of player false
+	turn: bool,
+	state: State,
+}
+
+impl Connect4 {
+	pub fn mask(&self) -> u64 {
+		self.board.0 | self.board.1
+	}
+	// stone of the player on the cell, None if empty
+	pub fn get(&self, x: u8, y: u8) -> Option<bool> {
+		let p = 1u64 << (x * H1 + y);
+		if self.board.0 & p != 0 {
+			Some(true)
+		} else if self.board.1 & p != 0 {
+			Some(false)
+		} else {
+			None
+		}
+	}
+}
+
+impl Game for Connect4 {
+	type M = u8; // column
+	type S = (u64, u64, bool);
+	type R = u8;
+	fn new(t: bool) -> Self {
+		Connect4 {
+			board: (0, 0),
+			turn: t,
+			state: State::Going,
+		}
+	}
+	fn turn(&self) -> bool {
+		self.turn
+	}
+	fn get_moves(&self) -> Vec<u8> {
+		let mask = self.mask();
+		(0..WIDTH).filter(|c| mask & top(*c) == 0).collect()
+	}
+	fn get_moves_sorted(&self) -> Vec<u8> {
+		let mask = self.mask();
+		ORDER
+			.iter()
+			.filter(|c| mask & top(**c) == 0)
+			.copied()
+			.collect()
+	}
+	fn get_static_state(&self) -> Self::S {
+		(self.board.0, self.board.1, self.turn)
+	}
+	fn state(&self) -> State {
+		self.state
+	}
+	fn mov(&mut self, m: &u8) {
+		let mask = self.mask();
+		let p = (mask + (1u64 << (m * H1))) & column(*m);
+		let b = if self.turn {
+			&mut self.board.0
+		} else {
+			&mut self.board.1
+		};
+		*b |= p;
+		self.state = if aligned(*b) {
+			if self.turn {
+				State::Win
+			} else {
+				State::Lose
+			}
+		} else if mask | p == FULL {
+			State::Draw
+		} else {
+			State::Going
+		};
+		self.turn = !self.turn;
+	}
+	fn mov_with_rollback(&mut self, m: &u8) -> Self::R {
+		self.mov(m);
+		*m
+	}
+	fn rollback(&mut self, m: Self::R) {
+		self.turn = !self.turn;
+		let col = self.mask() & column(m);
+		// highest stone of the column
+		let p = 1u64 << (63 - col.leading_zeros());
+		if self.turn {
+			self.board.0 ^= p;
+		} else {
+			self.board.1 ^= p;
+		}
+		self.state = State::Going;
+	}
+}
+
+impl std::fmt::Display for Connect4 {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		for y in (0..HEIGHT).rev() {
+			for x in 0..WIDTH {
+				write!(
+					f,
+					"{}",
+					match self.get(x, y) {
+						Some(true) => 'X',
+						Some(false) => 'O',
+						None => '.',
+					}
+				)?;
+			}
+			writeln!(f)?;
+		}
+		writeln!(f, "abcdefg")
+	}
+}
@@ -1,9 +1,24 @@
+use crate::amazons;
+use crate::breakthrough;
+use crate::checkers;
+use crate::connect4;
+use crate::dots_and_boxes;
 use crate::game::*;
+use crate::go;
 use crate::heuristic::Heuristic;
+use crate::hex;
 use crate::mancala;
+use crate::mnk;
+use crate::nim;
+use crate::old_tictactoe;
 use crate::othello;
+use crate::othello_endgame;
+use crate::oware;
 use crate::tablut;
-use crate::tictactoe;
+use crate::tafl;
+use crate::tafl::Bits;
+use crate::ultimate_tictactoe;
+use std::time::Instant;
 
 pub struct DefaultHeuristic;
 
@@ -18,8 +33,8 @@ impl Heuristic<tablut::Tablut> for DefaultHeuristic {
 				let na = g.a.count_ones() as i64;
 				let mut km = 0i64;
 				let kp = g.k.trailing_zeros();
-				let capturer = g.a | tablut::CAPTURE_AID;
-				let pass = !(g.a | g.d | tablut::BLOCK);
+				let capturer = g.a | g.capture_aid(false);
+				let pass = !(g.a | g.d | g.block());
 
 				let mut i = kp;
 				while (pass >> i) & 1 != 0 {
@@ -55,8 +70,35 @@ impl Heuristic<tablut::Tablut> for DefaultHeuristic {
 	}
 }
 
-impl Heuristic<tictactoe::Tictactoe> for DefaultHeuristic {
-	fn eval(g: &tictactoe::Tictactoe) -> i64 {
+impl<V: tafl::Variant> Heuristic<tafl::Tafl<V>> for DefaultHeuristic {
+	fn eval(g: &tafl::Tafl<V>) -> i64 {
+		match g.state() {
+			State::Win => i64::MAX - g.turn as i64,
+			State::Lose => i64::MIN + g.turn as i64,
+			State::Draw => 0,
+			State::Going => {
+				let nd = g.d.count() as i64;
+				let na = g.a.count() as i64;
+				// squares the king can slide to, escapes open in one move count a lot more
+				let mut km = 0i64;
+				let mut open = 0i64;
+				let s = g.stride() as isize;
+				for dir in [1, s, -1, -s] {
+					let mut i = (g.k as usize).wrapping_add_signed(dir);
+					while g.on_board(i) && !g.a.get(i) && !g.d.get(i) {
+						km += 1;
+						open += g.is_goal(i) as i64;
+						i = i.wrapping_add_signed(dir);
+					}
+				}
+				nd * 32 + km * 4 + open * 64 - na * 16 - (g.turn & 1) as i64
+			}
+		}
+	}
+}
+
+impl Heuristic<old_tictactoe::Tictactoe> for DefaultHeuristic {
+	fn eval(g: &old_tictactoe::Tictactoe) -> i64 {
 		match g.state() {
 			State::Win => 1,
 			State::Lose => -1,
@@ -65,6 +107,368 @@ impl Heuristic<tictactoe::Tictactoe> for DefaultHeuristic {
 	}
 }
 
+impl<const W: usize, const H: usize, const K: usize> Heuristic<mnk::Mnk<W, H, K>>
+	for DefaultHeuristic
+{
+	fn eval(g: &mnk::Mnk<W, H, K>) -> i64 {
+		match g.state() {
+			State::Win => 1 << 40,
+			State::Lose => -(1 << 40),
+			State::Draw => 0,
+			State::Going => {
+				// every window of K squares with stones of one color only,
+				// worth more the closer it is to K
+				let mut ans = 0i64;
+				// the squares that complete a window one stone from K
+				let (mut tb, mut tw) = (vec![], vec![]);
+				for (dx, dy) in [(1, 0), (0, 1), (1, 1), (1, -1)] {
+					for y in 0..H as i32 {
+						for x in 0..W as i32 {
+							let (ex, ey) = (x + dx * (K as i32 - 1), y + dy * (K as i32 - 1));
+							if g.get(ex, ey).is_none() {
+								continue;
+							}
+							let (mut b, mut w) = (0, 0);
+							let mut empty = (x, y);
+							for i in 0..K as i32 {
+								match g.get(x + dx * i, y + dy * i) {
+									Some(mnk::BLACK) => b += 1,
+									Some(mnk::WHITE) => w += 1,
+									_ => empty = (x + dx * i, y + dy * i),
+								}
+							}
+							for (n, m, t) in [(b, w, &mut tb), (w, b, &mut tw)] {
+								if n == K - 1 && m == 0 && !t.contains(&empty) {
+									t.push(empty);
+								}
+							}
+							ans += match (b, w) {
+								(0, 0) => 0,
+								(b, 0) => 1 << (2 * b),
+								(0, w) => -(1 << (2 * w)),
+								_ => 0,
+							};
+						}
+					}
+				}
+				// the side to move completes its own, the other side can only block one square
+				// of the ones that complete theirs. All of it stays far below finished games
+				// and the +-30000 that tells MinimaxFinal a line is decided
+				let (own, other) = if g.turn() == g.black() {
+					(&tb, &tw)
+				} else {
+					(&tw, &tb)
+				};
+				let threats = if !own.is_empty() {
+					1 << 13
+				} else if other.len() > 1 {
+					-(1 << 12)
+				} else {
+					0
+				};
+				let ans = if g.turn() == g.black() {
+					ans + threats
+				} else {
+					ans - threats
+				}
+				.clamp(-(1 << 14), 1 << 14);
+				if g.black() {
+					ans
+				} else {
+					-ans
+				}
+			}
+		}
+	}
+}
+
+impl Heuristic<ultimate_tictactoe::UltimateTictactoe> for DefaultHeuristic {
+	fn eval(g: &ultimate_tictactoe::UltimateTictactoe) -> i64 {
+		// lines with two of one player and nothing of the other
+		fn twos(own: u16, opp: u16) -> i64 {
+			ultimate_tictactoe::LINES
+				.iter()
+				.filter(|l| (own & **l).count_ones() == 2 && opp & **l == 0)
+				.count() as i64
+		}
+		const WEIGHTS: [i64; 9] = [3, 2, 3, 2, 4, 2, 3, 2, 3];
+		match g.state() {
+			State::Win => 32768,
+			State::Lose => -32768,
+			State::Draw => 0,
+			State::Going => {
+				let (mx, mo) = g.meta;
+				// a closed board that nobody won blocks the lines through it on the meta board
+				let drawn = g.closed() & !(mx | mo);
+				let mut ans = (twos(mx, mo | drawn) - twos(mo, mx | drawn)) * 64;
+				for i in 0..9 {
+					let bit = 1 << i;
+					if mx & bit != 0 {
+						ans += WEIGHTS[i as usize] * 16;
+					} else if mo & bit != 0 {
+						ans -= WEIGHTS[i as usize] * 16;
+					} else if g.closed() & bit == 0 {
+						let (x, o) = g.board(i);
+						ans += (twos(x, o) - twos(o, x)) * WEIGHTS[i as usize];
+					}
+				}
+				ans
+			}
+		}
+	}
+}
+
+impl<const N: usize> Heuristic<go::Go<N>> for DefaultHeuristic {
+	fn eval(g: &go::Go<N>) -> i64 {
+		// the area count as if the game ended now, finished games above any of it
+		match g.state() {
+			State::Going => g.half_points(),
+			_ => g.half_points() * 1024,
+		}
+	}
+}
+
+// the board as a circuit between the two edges of color c: empty cells are worth 1 ohm,
+// c's stones next to nothing and the other color's cut the current
+fn hex_resistance<const N: usize>(g: &hex::Hex<N>, c: u8) -> f64 {
+	let n = N * N;
+	const DIRS: [(i32, i32); 6] = [(1, 0), (-1, 0), (0, 1), (0, -1), (1, -1), (-1, 1)];
+	// cells next to the edge the current comes in from (0) and the one it leaves through
+	let edge = |x: i32, y: i32, e: i32| if c == hex::TRUE { y == e } else { x == e };
+	// cells walled off from both edges would float and leave the equations singular,
+	// only the ones a flood fill from the edges reaches are part of the circuit
+	let open = |x: i32, y: i32| matches!(g.get(x, y), Some(v) if v == hex::EMPTY || v == c);
+	let mut reached = vec![false; n];
+	let mut stack: Vec<(i32, i32)> = (0..n as i32)
+		.map(|i| (i % N as i32, i / N as i32))
+		.filter(|&(x, y)| open(x, y) && (edge(x, y, 0) || edge(x, y, N as i32 - 1)))
+		.collect();
+	while let Some((x, y)) = stack.pop() {
+		let i = y as usize * N + x as usize;
+		if reached[i] {
+			continue;
+		}
+		reached[i] = true;
+		for (dx, dy) in DIRS {
+			if open(x + dx, y + dy) {
+				stack.push((x + dx, y + dy));
+			}
+		}
+	}
+	let r = |x: i32, y: i32| match g.get(x, y) {
+		Some(_) if !reached[y as usize * N + x as usize] => None,
+		Some(hex::EMPTY) => Some(1.0),
+		Some(_) => Some(0.01),
+		None => None,
+	};
+	// Kirchhoff's equations for the voltages, 1 at the source and 0 at the sink. Cells
+	// only meet the ones up to N away in the order of the board, so the matrix is kept
+	// as its band, a[i * w + j + N - i] for row i and column j
+	let w = 2 * N + 1;
+	let at = |i: usize, j: usize| i * w + j + N - i;
+	let mut a = vec![0.0f64; n * w];
+	let mut b = vec![0.0f64; n];
+	let mut inflow = vec![0.0f64; n];
+	for i in 0..n {
+		let (x, y) = ((i % N) as i32, (i / N) as i32);
+		let ri = match r(x, y) {
+			Some(v) => v,
+			None => {
+				a[at(i, i)] = 1.0;
+				continue;
+			}
+		};
+		for (dx, dy) in DIRS {
+			if let Some(rj) = r(x + dx, y + dy) {
+				let cond = 1.0 / (ri + rj);
+				a[at(i, i)] += cond;
+				a[at(i, (y + dy) as usize * N + (x + dx) as usize)] -= cond;
+			}
+		}
+		if edge(x, y, 0) {
+			inflow[i] = 1.0 / ri;
+			a[at(i, i)] += inflow[i];
+			b[i] += inflow[i];
+		}
+		if edge(x, y, N as i32 - 1) {
+			a[at(i, i)] += 1.0 / ri;
+		}
+	}
+	// Gaussian elimination inside the band, the matrix is symmetric and positive
+	// definite once every cell left is tied to an edge, so it needs no pivoting
+	for k in 0..n {
+		let d = a[at(k, k)];
+		for i in k + 1..n.min(k + N + 1) {
+			let f = a[at(i, k)] / d;
+			if f != 0.0 {
+				for j in k..n.min(k + N + 1) {
+					a[at(i, j)] -= f * a[at(k, j)];
+				}
+				b[i] -= f * b[k];
+			}
+		}
+	}
+	let mut v = vec![0.0f64; n];
+	for i in (0..n).rev() {
+		let s: f64 = (i + 1..n.min(i + N + 1)).map(|j| a[at(i, j)] * v[j]).sum();
+		v[i] = (b[i] - s) / a[at(i, i)];
+	}
+	let current: f64 = (0..n).map(|i| inflow[i] * (1.0 - v[i])).sum();
+	1.0 / current
+}
+
+impl<const N: usize> Heuristic<hex::Hex<N>> for DefaultHeuristic {
+	fn eval(g: &hex::Hex<N>) -> i64 {
+		match g.state() {
+			State::Win => 1 << 20,
+			State::Lose => -(1 << 20),
+			_ => {
+				// the log of how much easier the current flows for player true
+				let rt = hex_resistance(g, hex::TRUE);
+				let rf = hex_resistance(g, hex::FALSE);
+				((rf / rt).ln() * 256.0).clamp(-65536.0, 65536.0) as i64
+			}
+		}
+	}
+}
+
+impl Heuristic<breakthrough::Breakthrough> for DefaultHeuristic {
+	fn eval(g: &breakthrough::Breakthrough) -> i64 {
+		match g.state() {
+			State::Win => 1 << 20,
+			State::Lose => -(1 << 20),
+			_ => {
+				// a pawn one step from the far row wins unless it's taken first
+				let (t, f) = g.board;
+				let (near_t, near_f) = (
+					t & (breakthrough::ROW_8 >> 8),
+					f & (breakthrough::ROW_1 << 8),
+				);
+				if g.turn() && near_t != 0 {
+					return 1 << 19;
+				}
+				if !g.turn() && near_f != 0 {
+					return -(1 << 19);
+				}
+				// pawns, and more for the ones further ahead
+				let mut ans = 0i64;
+				for y in 0..8 {
+					let row = 0xffu64 << (y * 8);
+					ans += (t & row).count_ones() as i64 * (64 + y * y);
+					ans -= (f & row).count_ones() as i64 * (64 + (7 - y) * (7 - y));
+				}
+				ans
+			}
+		}
+	}
+}
+
+// queen move distance from the amazons to every square, u8::MAX for the unreachable
+fn amazons_distances(amazons: u128, occupied: u128) -> [u8; 100] {
+	let mut ans = [u8::MAX; 100];
+	let mut reached = amazons;
+	let mut frontier = amazons;
+	let mut d = 0;
+	while frontier != 0 {
+		let mut next = 0;
+		for p in amazons::squares(frontier) {
+			next |= amazons::queen_moves(p, occupied);
+		}
+		next &= !reached;
+		d += 1;
+		for p in amazons::squares(next) {
+			ans[p as usize] = d;
+		}
+		reached |= next;
+		frontier = next;
+	}
+	ans
+}
+
+impl Heuristic<amazons::Amazons> for DefaultHeuristic {
+	fn eval(g: &amazons::Amazons) -> i64 {
+		match g.state() {
+			State::Win => 1 << 20,
+			State::Lose => -(1 << 20),
+			_ => {
+				// territory: the empty squares a player's amazons reach in fewer moves,
+				// ties go to the player to move
+				let occupied = g.occupied();
+				let dt = amazons_distances(g.amazons.0, occupied);
+				let df = amazons_distances(g.amazons.1, occupied);
+				let mut ans = 0i64;
+				for (t, f) in dt.iter().zip(df.iter()) {
+					ans += match t.cmp(f) {
+						std::cmp::Ordering::Less => 16,
+						std::cmp::Ordering::Greater => -16,
+						_ if *t == u8::MAX => 0,
+						_ if g.turn() => 4,
+						_ => -4,
+					};
+				}
+				ans
+			}
+		}
+	}
+}
+
+impl Heuristic<checkers::Checkers> for DefaultHeuristic {
+	fn eval(g: &checkers::Checkers) -> i64 {
+		match g.state() {
+			State::Win => 1 << 20,
+			State::Lose => -(1 << 20),
+			State::Draw => 0,
+			State::Going => {
+				// material, men a little more the further they are, and the back rows kept
+				let (t, f) = g.pieces;
+				let (tm, fm) = (t & !g.kings, f & !g.kings);
+				let mut ans = 160 * ((t & g.kings).count_ones() as i64 - (f & g.kings).count_ones() as i64);
+				ans += 8 * ((tm & 0xf).count_ones() as i64 - (fm & (0xf << 28)).count_ones() as i64);
+				for y in 0..8 {
+					let row = 0xf << (4 * y);
+					ans += (tm & row).count_ones() as i64 * (100 + 2 * y);
+					ans -= (fm & row).count_ones() as i64 * (100 + 2 * (7 - y));
+				}
+				ans
+			}
+		}
+	}
+}
+
+// nothing but the end, the games with a theory are there to check the searches
+impl<const N: usize> Heuristic<nim::Nim<N>> for DefaultHeuristic {
+	fn eval(g: &nim::Nim<N>) -> i64 {
+		match g.state() {
+			State::Win => 1 << 20,
+			State::Lose => -(1 << 20),
+			_ => 0,
+		}
+	}
+}
+
+impl Heuristic<nim::Subtraction> for DefaultHeuristic {
+	fn eval(g: &nim::Subtraction) -> i64 {
+		match g.state() {
+			State::Win => 1 << 20,
+			State::Lose => -(1 << 20),
+			_ => 0,
+		}
+	}
+}
+
+impl<const W: usize, const H: usize> Heuristic<dots_and_boxes::DotsAndBoxes<W, H>>
+	for DefaultHeuristic
+{
+	fn eval(g: &dots_and_boxes::DotsAndBoxes<W, H>) -> i64 {
+		let diff = g.boxes.0 as i64 - g.boxes.1 as i64;
+		match g.state() {
+			// finished games above any of the boxes so far
+			State::Going => diff * 256,
+			_ => diff << 16,
+		}
+	}
+}
+
 impl Heuristic<othello::Othello> for DefaultHeuristic {
 	fn eval(g: &othello::Othello) -> i64 {
 		const WEIGHTS: [i64; 64] = [
@@ -73,9 +477,8 @@ impl Heuristic<othello::Othello> for DefaultHeuristic {
 			-4, -3, 4, -3, 2, 2, 2, 2, -3, 4,
 		];
 		match g.state() {
-			State::Win => 256,
-			State::Lose => -256,
-			State::Draw => 0,
+			// the final disc difference, a disc is worth more than any position
+			State::Win | State::Lose | State::Draw => g.disc_score() * 256,
 			State::Going => {
 				let mut ans = 0i64;
 				for i in 0..64 {
@@ -93,6 +496,58 @@ impl Heuristic<othello::Othello> for DefaultHeuristic {
 			}
 		}
 	}
+	fn solve(g: &othello::Othello, deadline: Instant) -> Option<(u8, i64)> {
+		let (t, f) = g.stones();
+		if (t | f).count_zeros() > othello_endgame::SOLVE_EMPTIES {
+			return None;
+		}
+		othello_endgame::solve(g, deadline).map(|(m, v)| (m, v * 256))
+	}
+}
+
+impl Heuristic<oware::Oware> for DefaultHeuristic {
+	fn eval(g: &oware::Oware) -> i64 {
+		match g.state() {
+			State::Win => 32768,
+			State::Lose => -32768,
+			State::Draw => 0,
+			State::Going => {
+				let t: i64 = g.a[..6].iter().map(|x| *x as i64).sum();
+				let f: i64 = g.a[6..].iter().map(|x| *x as i64).sum();
+				// houses with 1 or 2 seeds are the ones that can be taken next
+				let weak = |h: &[u8]| h.iter().filter(|x| (1..=2).contains(*x)).count() as i64;
+				(g.store[0] as i64 - g.store[1] as i64) * 16 + (t - f)
+					- (weak(&g.a[..6]) - weak(&g.a[6..])) * 2
+			}
+		}
+	}
+}
+
+impl Heuristic<connect4::Connect4> for DefaultHeuristic {
+	fn eval(g: &connect4::Connect4) -> i64 {
+		match g.state() {
+			State::Win => 32768,
+			State::Lose => -32768,
+			State::Draw => 0,
+			State::Going => {
+				let mask = g.mask();
+				let tt = connect4::threats(g.board.0, mask);
+				let tf = connect4::threats(g.board.1, mask);
+				// cells that can be played right now
+				let playable = (mask + connect4::BOTTOM) & connect4::FULL;
+				let now = if g.turn() {
+					(tt & playable != 0) as i64 * 1024
+				} else {
+					(tf & playable != 0) as i64 * -1024
+				};
+				let center = connect4::column(3);
+				now
+					+ 16 * (tt.count_ones() as i64 - tf.count_ones() as i64)
+					+ 3
+						* ((g.board.0 & center).count_ones() as i64 - (g.board.1 & center).count_ones() as i64)
+			}
+		}
+	}
 }
 
 impl Heuristic<mancala::Mancala> for DefaultHeuristic {
@@ -0,0 +1,159 @@
+// Dots and Boxes on W x H boxes: a move draws a line between two neighbouring dots,
+// whoever draws the fourth side of a box takes it and has to move again. The game ends
+// when all lines are drawn, most boxes wins.
+// Lines are bits of a u64: horizontal ones y * W + x from dot (x, y) to (x + 1, y),
+// then vertical ones from dot (x, y) to (x, y + 1).
+use crate::game::*;
+
+#[derive(Debug, Hash, Clone, Copy, PartialEq, Eq)]
+pub struct DotsAndBoxes<const W: usize, const H: usize> {
+	pub lines: u64,
+	// boxes of player true and of player false
+	pub boxes: (u8, u8),
+	turn: bool,
+}
+
+// the largest square board that fits
+pub type Dots5 = DotsAndBoxes<5, 5>;
+
+impl<const W: usize, const H: usize> DotsAndBoxes<W, H> {
+	pub const LINES: usize = W * (H + 1) + (W + 1) * H;
+	pub fn horizontal(x: usize, y: usize) -> u8 {
+		(y * W + x) as u8
+	}
+	pub fn vertical(x: usize, y: usize) -> u8 {
+		(W * (H + 1) + y * (W + 1) + x) as u8
+	}
+	// the four sides of box (x, y)
+	pub fn sides(x: usize, y: usize) -> u64 {
+		(1 << Self::horizontal(x, y))
+			| (1 << Self::horizontal(x, y + 1))
+			| (1 << Self::vertical(x, y))
+			| (1 << Self::vertical(x + 1, y))
+	}
+	// the boxes on either side of line l
+	pub fn boxes_of(l: u8) -> impl Iterator<Item = (usize, usize)> {
+		let l = l as usize;
+		let (a, b) = if l < W * (H + 1) {
+			let (x, y) = (l % W, l / W);
+			((y > 0).then(|| (x, y - 1)), (y < H).then_some((x, y)))
+		} else {
+			let l = l - W * (H + 1);
+			let (x, y) = (l % (W + 1), l / (W + 1));
+			((x > 0).then(|| (x - 1, y)), (x < W).then_some((x, y)))
+		};
+		a.into_iter().chain(b)
+	}
+	// the boxes line l closes
+	pub fn closes(&self, l: u8) -> u8 {
+		let lines = self.lines | (1 << l);
+		Self::boxes_of(l)
+			.filter(|(x, y)| lines & Self::sides(*x, *y) == Self::sides(*x, *y))
+			.count() as u8
+	}
+}
+
+impl<const W: usize, const H: usize> Game for DotsAndBoxes<W, H> {
+	type M = u8; // line
+	type S = (u64, (u8, u8), bool);
+	type R = (u8, bool); // the line and who drew it
+	fn new(t: bool) -> Self {
+		assert!(Self::LINES <= 64);
+		DotsAndBoxes {
+			lines: 0,
+			boxes: (0, 0),
+			turn: t,
+		}
+	}
+	fn turn(&self) -> bool {
+		self.turn
+	}
+	fn get_moves(&self) -> Vec<u8> {
+		(0..Self::LINES as u8)
+			.filter(|l| self.lines & (1 << l) == 0)
+			.collect()
+	}
+	fn get_moves_sorted(&self) -> Vec<u8> {
+		// closing boxes first
+		let mut ans = self.get_moves();
+		ans.sort_by_key(|l| std::cmp::Reverse(self.closes(*l)));
+		ans
+	}
+	fn get_static_state(&self) -> Self::S {
+		(self.lines, self.boxes, self.turn)
+	}
+	fn state(&self) -> State {
+		if self.lines.count_ones() as usize != Self::LINES {
+			return State::Going;
+		}
+		match self.boxes.0.cmp(&self.boxes.1) {
+			std::cmp::Ordering::Greater => State::Win,
+			std::cmp::Ordering::Less => State::Lose,
+			std::cmp::Ordering::Equal => State::Draw,
+		}
+	}
+	fn mov(&mut self, m: &u8) {
+		let n = self.closes(*m);
+		self.lines |= 1 << m;
+		if n == 0 {
+			self.turn = !self.turn;
+		} else if self.turn {
+			self.boxes.0 += n;
+		} else {
+			self.boxes.1 += n;
+		}
+	}
+	fn mov_with_rollback(&mut self, m: &u8) -> Self::R {
+		let rb = (*m, self.turn);
+		self.mov(m);
+		rb
+	}
+	fn rollback(&mut self, (m, turn): Self::R) {
+		self.lines &= !(1 << m);
+		let n = self.closes(m);
+		self.turn = turn;
+		if turn {
+			self.boxes.0 -= n;
+		} else {
+			self.boxes.1 -= n;
+		}
+	}
+	fn score(&self) -> Option<i64> {
+		(self.state() != State::Going).then(|| self.boxes.0 as i64 - self.boxes.1 as i64)
+	}
+}
+
+impl<const W: usize, const H: usize> std::fmt::Display for DotsAndBoxes<W, H> {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		let has = |l: u8| self.lines & (1 << l) != 0;
+		for y in (0..=H).rev() {
+			for x in 0..W {
+				write!(
+					f,
+					"+{}",
+					if has(Self::horizontal(x, y)) {
+						"-"
+					} else {
+						" "
+					}
+				)?;
+			}
+			writeln!(f, "+")?;
+			if y > 0 {
+				for x in 0..=W {
+					write!(
+						f,
+						"{} ",
+						if has(Self::vertical(x, y - 1)) {
+							"|"
+						} else {
+							" "
+						}
+					)?;
+				}
+				writeln!(f)?;
+			}
+		}
+		Ok(())
+	}
+}
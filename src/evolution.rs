@@ -0,0 +1,236 @@
+// Evolution of the weights of a FeatureHeuristic, for when the results of single positions
+// say too little: a population of weight vectors plays a round robin with MinimaxFixed at
+// depth D, each pair both ways after a few random moves, the better half survives and the
+// other half is made of children of the survivors, crossed over and mutated.
+// Everything random comes from the seed and the generation, so a run saved to a
+// checkpoint and resumed plays the same games as one that never stopped.
+// The players of a game take their weights from the thread playing it, not from the
+// weight slots, so games on other threads and weights set meanwhile don't change them.
+use crate::ai::Ai;
+use crate::feature_heuristic::*;
+use crate::game::*;
+use crate::heuristic::Heuristic;
+use crate::minimax_fixed::MinimaxFixed;
+use rand::seq::SliceRandom;
+use rand::Rng;
+use rand::SeedableRng;
+use rand_xoshiro::Xoroshiro128Plus;
+use std::cell::RefCell;
+use std::time::Duration;
+
+thread_local! {
+	// the weights of players 0 and 1 of the game this thread plays
+	static PLAYERS: RefCell<[Vec<i64>; 2]> = const { RefCell::new([Vec::new(), Vec::new()]) };
+}
+
+// FeatureHeuristic with the weights of player P of this thread's game
+pub struct Player<const P: usize>;
+
+impl<G: Features, const P: usize> Heuristic<G> for Player<P> {
+	fn eval(g: &G) -> i64 {
+		match g.state() {
+			State::Win => WIN,
+			State::Lose => -WIN,
+			State::Draw => 0,
+			State::Going => {
+				let f = g.features();
+				PLAYERS.with(|w| dot(&w.borrow()[P], f.as_ref()))
+			}
+		}
+	}
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Population {
+	pub seed: u64,
+	pub generation: u64,
+	// the best of the last generation first
+	pub members: Vec<Vec<i64>>,
+}
+
+impl Population {
+	// start and size - 1 mutations of it
+	pub fn new(start: &[i64], size: usize, mutation: i64, seed: u64) -> Self {
+		let mut ans = Population {
+			seed,
+			generation: 0,
+			members: vec![start.to_vec()],
+		};
+		let mut rng = ans.rng();
+		while ans.members.len() < size {
+			let mut w = start.to_vec();
+			mutate(&mut w, mutation, &mut rng);
+			ans.members.push(w);
+		}
+		ans
+	}
+	fn rng(&self) -> Xoroshiro128Plus {
+		Xoroshiro128Plus::seed_from_u64(self.seed ^ self.generation.wrapping_mul(0x9e3779b97f4a7c15))
+	}
+	// "seed <s>", "generation <g>", then one line of weights per member
+	pub fn write(&self) -> String {
+		let mut ans = format!("seed {}\ngeneration {}\n", self.seed, self.generation);
+		for w in self.members.iter() {
+			let w: Vec<String> = w.iter().map(|x| x.to_string()).collect();
+			ans += &w.join(" ");
+			ans += "\n";
+		}
+		ans
+	}
+	pub fn read(s: &str) -> Result<Self, String> {
+		let mut lines = s.lines();
+		let mut header = |name: &str| -> Result<u64, String> {
+			lines
+				.next()
+				.and_then(|l| l.strip_prefix(name))
+				.and_then(|x| x.trim().parse().ok())
+				.ok_or(format!("no {}", name))
+		};
+		let seed = header("seed")?;
+		let generation = header("generation")?;
+		let members = lines
+			.filter(|l| !l.trim().is_empty())
+			.map(|l| {
+				l.split_whitespace()
+					.map(|x| x.parse().map_err(|_| format!("can't read weight {}", x)))
+					.collect::<Result<Vec<i64>, String>>()
+			})
+			.collect::<Result<Vec<Vec<i64>>, String>>()?;
+		if members.is_empty() || members.iter().any(|w| w.len() != members[0].len()) {
+			return Err("members of different sizes".to_string());
+		}
+		Ok(Population {
+			seed,
+			generation,
+			members,
+		})
+	}
+	pub fn save(&self, path: &str) -> Result<(), String> {
+		std::fs::write(path, self.write()).map_err(|e| format!("can't write {}: {}", path, e))
+	}
+	pub fn load(path: &str) -> Result<Self, String> {
+		Self::read(&std::fs::read_to_string(path).map_err(|e| format!("can't read {}: {}", path, e))?)
+	}
+}
+
+// adds up to mutation either way to about one weight in four, and to at least one
+pub fn mutate(w: &mut [i64], mutation: i64, rng: &mut Xoroshiro128Plus) {
+	let i = rng.gen_range(0..w.len());
+	w[i] += rng.gen_range(-mutation..=mutation);
+	for x in w.iter_mut() {
+		if rng.gen_range(0..4) == 0 {
+			*x += rng.gen_range(-mutation..=mutation);
+		}
+	}
+}
+
+// a game between weights a as player true and b, after the random moves
+pub fn play<G: Features, const D: u32>(a: &[i64], b: &[i64], random: &[G::M]) -> State {
+	PLAYERS.with(|w| *w.borrow_mut() = [a.to_vec(), b.to_vec()]);
+	let mut pa = MinimaxFixed::<G, Player<0>, D>::new(true);
+	let mut pb = MinimaxFixed::<G, Player<1>, D>::new(true);
+	let mut g = G::new(true);
+	for m in random {
+		pa.mov(m);
+		pb.mov(m);
+		g.mov(m);
+	}
+	while g.state() == State::Going {
+		let m = if g.turn() {
+			pa.get_mov(Duration::ZERO)
+		} else {
+			pb.get_mov(Duration::ZERO)
+		};
+		pa.mov(&m);
+		pb.mov(&m);
+		g.mov(&m);
+	}
+	g.state()
+}
+
+// random_plies random moves, fewer if the game ends
+fn opening<G: Game>(random_plies: usize, rng: &mut Xoroshiro128Plus) -> Vec<G::M> {
+	let mut g = G::new(true);
+	let mut ans = vec![];
+	while ans.len() < random_plies && g.state() == State::Going {
+		let m = *g.get_moves().choose(rng).unwrap();
+		g.mov(&m);
+		ans.push(m);
+	}
+	ans
+}
+
+// 2 points for a win and 1 for a draw, every pair plays the same opening both ways
+pub fn fitness<G: Features, const D: u32>(
+	members: &[Vec<i64>],
+	random_plies: usize,
+	rng: &mut Xoroshiro128Plus,
+) -> Vec<i64> {
+	let mut ans = vec![0; members.len()];
+	for i in 0..members.len() {
+		for j in i + 1..members.len() {
+			let random = opening::<G>(random_plies, rng);
+			for (a, b) in [(i, j), (j, i)] {
+				match play::<G, D>(&members[a], &members[b], &random) {
+					State::Win => ans[a] += 2,
+					State::Lose => ans[b] += 2,
+					_ => {
+						ans[a] += 1;
+						ans[b] += 1;
+					}
+				}
+			}
+		}
+	}
+	ans
+}
+
+// plays the generation, sorts it best first and replaces the worse half.
+// Returns the sorted scores
+pub fn next_generation<G: Features, const D: u32>(
+	p: &mut Population,
+	random_plies: usize,
+	mutation: i64,
+) -> Vec<i64> {
+	let mut rng = p.rng();
+	let scores = fitness::<G, D>(&p.members, random_plies, &mut rng);
+	// stable, so among equals the older ones stay first
+	let mut order: Vec<usize> = (0..p.members.len()).collect();
+	order.sort_by_key(|i| std::cmp::Reverse(scores[*i]));
+	let mut members: Vec<Vec<i64>> = order.iter().map(|i| p.members[*i].clone()).collect();
+	let survivors = members.len().div_ceil(2);
+	for k in survivors..members.len() {
+		let a = &members[rng.gen_range(0..survivors)];
+		let b = &members[rng.gen_range(0..survivors)];
+		let mut child: Vec<i64> = a
+			.iter()
+			.zip(b.iter())
+			.map(|(x, y)| if rng.gen() { *x } else { *y })
+			.collect();
+		mutate(&mut child, mutation, &mut rng);
+		members[k] = child;
+	}
+	p.members = members;
+	p.generation += 1;
+	order.iter().map(|i| scores[*i]).collect()
+}
+
+// generations more generations, saved to checkpoint after each one if given.
+// The best weights of the last generation are p.members[0]
+pub fn evolve<G: Features, const D: u32>(
+	p: &mut Population,
+	generations: u64,
+	random_plies: usize,
+	mutation: i64,
+	checkpoint: Option<&str>,
+) -> Result<(), String> {
+	for _ in 0..generations {
+		let scores = next_generation::<G, D>(p, random_plies, mutation);
+		eprintln!("generation {} scores {:?}", p.generation, scores);
+		if let Some(path) = checkpoint {
+			p.save(path)?;
+		}
+	}
+	eprint!("{}", write_weights::<G>(&p.members[0]));
+	Ok(())
+}
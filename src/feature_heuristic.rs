@@ -0,0 +1,330 @@
+// Linear heuristics: a game lists features of a position, the eval is their dot product
+// with a weight vector. Weights are kept per game and slot, so agents with different
+// slots can play with different weights in the same process, and can be read from and
+// written to files of "name value" lines, # starts a comment.
+// Slots nobody set use the game's default weights. Each thread keeps a copy of the
+// weights its evals use, taken again only after some weights were set.
+use crate::checkers;
+use crate::game::*;
+use crate::heuristic::Heuristic;
+use crate::mancala;
+use crate::othello;
+use crate::tablut;
+use crate::tablut_heuristics;
+use std::any::TypeId;
+use std::cell::RefCell;
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::Ordering;
+use std::sync::RwLock;
+
+// finished games, above any weighted sum
+pub const WIN: i64 = 1 << 40;
+
+pub trait Features: Game + 'static {
+	const NAMES: &'static [&'static str];
+	const WEIGHTS: &'static [i64];
+	// one value for each of NAMES
+	type F: AsRef<[i64]>;
+	// for going positions, higher is better for player true
+	fn features(&self) -> Self::F;
+}
+
+// game type, slot and weights
+static WEIGHTS: RwLock<Vec<(TypeId, usize, Vec<i64>)>> = RwLock::new(Vec::new());
+// how many times weights were set
+static GENERATION: AtomicU64 = AtomicU64::new(0);
+
+// a thread's copy of the weights of a game and slot
+struct Cached {
+	game: TypeId,
+	slot: usize,
+	generation: u64,
+	weights: Vec<i64>,
+}
+
+thread_local! {
+	static CACHE: RefCell<Vec<Cached>> = const { RefCell::new(Vec::new()) };
+}
+
+pub fn weights<G: Features>(slot: usize) -> Vec<i64> {
+	WEIGHTS
+		.read()
+		.unwrap()
+		.iter()
+		.find(|x| x.0 == TypeId::of::<G>() && x.1 == slot)
+		.map_or_else(|| G::WEIGHTS.to_vec(), |x| x.2.clone())
+}
+
+pub fn set_weights<G: Features>(slot: usize, w: Vec<i64>) {
+	assert_eq!(w.len(), G::NAMES.len());
+	let mut all = WEIGHTS.write().unwrap();
+	match all
+		.iter_mut()
+		.find(|x| x.0 == TypeId::of::<G>() && x.1 == slot)
+	{
+		Some(x) => x.2 = w,
+		None => all.push((TypeId::of::<G>(), slot, w)),
+	}
+	GENERATION.fetch_add(1, Ordering::Release);
+}
+
+// f of the weights of slot, from this thread's copy while it is up to date
+fn with_weights<G: Features, R>(slot: usize, f: impl FnOnce(&[i64]) -> R) -> R {
+	let generation = GENERATION.load(Ordering::Acquire);
+	CACHE.with(|c| {
+		let mut c = c.borrow_mut();
+		let i = match c
+			.iter()
+			.position(|x| x.game == TypeId::of::<G>() && x.slot == slot)
+		{
+			Some(i) => i,
+			None => {
+				c.push(Cached {
+					game: TypeId::of::<G>(),
+					slot,
+					generation: u64::MAX,
+					weights: vec![],
+				});
+				c.len() - 1
+			}
+		};
+		if c[i].generation != generation {
+			c[i].generation = generation;
+			c[i].weights = weights::<G>(slot);
+		}
+		f(&c[i].weights)
+	})
+}
+
+// features missing from the file keep their default weight
+pub fn read_weights<G: Features>(s: &str) -> Result<Vec<i64>, String> {
+	let mut w = G::WEIGHTS.to_vec();
+	for line in s.lines() {
+		let line = line.split('#').next().unwrap().trim();
+		if line.is_empty() {
+			continue;
+		}
+		let (name, value) = line
+			.split_once(char::is_whitespace)
+			.ok_or(format!("no value in {}", line))?;
+		let i = G::NAMES
+			.iter()
+			.position(|x| *x == name)
+			.ok_or(format!("unknown feature {}", name))?;
+		w[i] = value
+			.trim()
+			.parse()
+			.map_err(|_| format!("can't read weight {}", value.trim()))?;
+	}
+	Ok(w)
+}
+
+pub fn write_weights<G: Features>(w: &[i64]) -> String {
+	G::NAMES
+		.iter()
+		.zip(w.iter())
+		.map(|(n, v)| format!("{} {}\n", n, v))
+		.collect()
+}
+
+pub fn load_weights<G: Features>(slot: usize, path: &str) -> Result<(), String> {
+	let s = std::fs::read_to_string(path).map_err(|e| format!("can't read {}: {}", path, e))?;
+	set_weights::<G>(slot, read_weights::<G>(&s)?);
+	Ok(())
+}
+
+pub fn save_weights<G: Features>(slot: usize, path: &str) -> Result<(), String> {
+	std::fs::write(path, write_weights::<G>(&weights::<G>(slot)))
+		.map_err(|e| format!("can't write {}: {}", path, e))
+}
+
+pub fn dot(w: &[i64], f: &[i64]) -> i64 {
+	w.iter().zip(f.iter()).map(|(w, f)| w * f).sum()
+}
+
+pub struct FeatureHeuristic<const SLOT: usize>;
+
+impl<G: Features, const SLOT: usize> Heuristic<G> for FeatureHeuristic<SLOT> {
+	fn eval(g: &G) -> i64 {
+		match g.state() {
+			State::Win => WIN,
+			State::Lose => -WIN,
+			State::Draw => 0,
+			State::Going => {
+				let f = g.features();
+				with_weights::<G, _>(SLOT, |w| dot(w, f.as_ref()))
+			}
+		}
+	}
+}
+
+impl Features for tablut::Tablut {
+	const NAMES: &'static [&'static str] = &tablut_heuristics::FM_FEATURES;
+	const WEIGHTS: &'static [i64] = &tablut_heuristics::FM_WEIGHTS;
+	type F = [i64; 5];
+	fn features(&self) -> [i64; 5] {
+		tablut_heuristics::fm_features(self)
+	}
+}
+
+// the six and then each square, 1 with a disc of player true, -1 of player false
+const OTHELLO_NAMES: [&str; 70] = [
+	"discs",
+	"mobility",
+	"corners",
+	"x_squares",
+	"edges",
+	"stable",
+	"a1",
+	"b1",
+	"c1",
+	"d1",
+	"e1",
+	"f1",
+	"g1",
+	"h1",
+	"a2",
+	"b2",
+	"c2",
+	"d2",
+	"e2",
+	"f2",
+	"g2",
+	"h2",
+	"a3",
+	"b3",
+	"c3",
+	"d3",
+	"e3",
+	"f3",
+	"g3",
+	"h3",
+	"a4",
+	"b4",
+	"c4",
+	"d4",
+	"e4",
+	"f4",
+	"g4",
+	"h4",
+	"a5",
+	"b5",
+	"c5",
+	"d5",
+	"e5",
+	"f5",
+	"g5",
+	"h5",
+	"a6",
+	"b6",
+	"c6",
+	"d6",
+	"e6",
+	"f6",
+	"g6",
+	"h6",
+	"a7",
+	"b7",
+	"c7",
+	"d7",
+	"e7",
+	"f7",
+	"g7",
+	"h7",
+	"a8",
+	"b8",
+	"c8",
+	"d8",
+	"e8",
+	"f8",
+	"g8",
+	"h8",
+];
+const OTHELLO_WEIGHTS: [i64; 70] = {
+	let mut w = [0; 70];
+	let six = [1, 8, 64, -24, 4, 16];
+	let mut i = 0;
+	while i < 70 {
+		w[i] = if i < 6 {
+			six[i]
+		} else {
+			othello::WEIGHTS[i - 6]
+		};
+		i += 1;
+	}
+	w
+};
+
+impl Features for othello::Othello {
+	const NAMES: &'static [&'static str] = &OTHELLO_NAMES;
+	const WEIGHTS: &'static [i64] = &OTHELLO_WEIGHTS;
+	type F = [i64; 70];
+	fn features(&self) -> [i64; 70] {
+		const CORNERS: u64 = 0x8100000000000081;
+		const X_SQUARES: u64 = 0x0042000000004200;
+		const EDGES: u64 = 0xff818181818181ff & !CORNERS;
+		let (t, f) = self.stones();
+		let diff = |mask: u64| (t & mask).count_ones() as i64 - (f & mask).count_ones() as i64;
+		let mut ans = [0; 70];
+		ans[..6].copy_from_slice(&[
+			diff(u64::MAX),
+			othello::moves(t, f).count_ones() as i64 - othello::moves(f, t).count_ones() as i64,
+			diff(CORNERS),
+			diff(X_SQUARES),
+			diff(EDGES),
+			othello::stable(t, f).count_ones() as i64 - othello::stable(f, t).count_ones() as i64,
+		]);
+		for i in 0..64 {
+			ans[i + 6] = diff(1 << i);
+		}
+		ans
+	}
+}
+
+// the seeds in each pit, player true's side first
+impl Features for mancala::Mancala {
+	const NAMES: &'static [&'static str] = &[
+		"pit_1",
+		"pit_2",
+		"pit_3",
+		"pit_4",
+		"pit_5",
+		"pit_6",
+		"store",
+		"opp_pit_1",
+		"opp_pit_2",
+		"opp_pit_3",
+		"opp_pit_4",
+		"opp_pit_5",
+		"opp_pit_6",
+		"opp_store",
+	];
+	const WEIGHTS: &'static [i64] = &[7, 6, 5, 4, 3, 2, 8, -7, -6, -5, -4, -3, -2, -8];
+	type F = [i64; 14];
+	fn features(&self) -> [i64; 14] {
+		self.a.map(|x| x as i64)
+	}
+}
+
+impl Features for checkers::Checkers {
+	const NAMES: &'static [&'static str] = &["men", "kings", "back_row", "advance"];
+	const WEIGHTS: &'static [i64] = &[100, 160, 8, 2];
+	type F = [i64; 4];
+	fn features(&self) -> [i64; 4] {
+		let (t, f) = self.pieces;
+		let (tm, fm) = (t & !self.kings, f & !self.kings);
+		let count = |b: u32| b.count_ones() as i64;
+		// rows the men have gone forward
+		let mut advance = 0;
+		for y in 0..8 {
+			let row = 0xf << (4 * y);
+			advance += count(tm & row) * y - count(fm & row) * (7 - y);
+		}
+		[
+			count(tm) - count(fm),
+			count(t & self.kings) - count(f & self.kings),
+			count(tm & 0xf) - count(fm & (0xf << 28)),
+			advance,
+		]
+	}
+}
@@ -22,4 +22,9 @@ pub trait Game: Clone + Debug + Display {
 	fn mov(&mut self, m: &Self::M);
 	fn mov_with_rollback(&mut self, m: &Self::M) -> Self::R;
 	fn rollback(&mut self, rb: Self::R);
+	// the final score for player true once the game is over,
+	// for games where the margin counts and not just who won
+	fn score(&self) -> Option<i64> {
+		None
+	}
 }
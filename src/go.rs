@@ -0,0 +1,281 @@
+// Go on N x N boards up to 13x13 with area scoring: stones plus the empty regions
+// that touch only one color. Suicide is illegal, so is any move that brings back
+// a board seen before (positional superko). Two passes in a row end the game, so does
+// MAX_MOVES times the board size, for playouts that would go on for long.
+// The second player gets the komi, kept in half points so that 13 is 6.5.
+use crate::game::*;
+
+pub const EMPTY: u8 = 0;
+// stones of player true and of player false
+pub const TRUE: u8 = 1;
+pub const FALSE: u8 = 2;
+pub const PASS: u16 = u16::MAX;
+pub const MAX_MOVES: usize = 3;
+pub const KOMI: i32 = 15;
+
+// set of points, enough for 13x13
+pub type Points = [u64; 3];
+
+fn has(s: &Points, p: usize) -> bool {
+	(s[p / 64] >> (p % 64)) & 1 != 0
+}
+fn add(s: &mut Points, p: usize) {
+	s[p / 64] |= 1 << (p % 64);
+}
+fn points(s: Points) -> impl Iterator<Item = usize> {
+	(0..3).flat_map(move |i| {
+		let mut b = s[i];
+		std::iter::from_fn(move || {
+			if b == 0 {
+				return None;
+			}
+			let p = b.trailing_zeros() as usize;
+			b &= b - 1;
+			Some(i * 64 + p)
+		})
+	})
+}
+
+// zobrist key of a stone of color c on p, splitmix64 of the pair
+fn key(p: usize, c: u8) -> u64 {
+	let mut z = (p as u64 * 2 + c as u64).wrapping_add(0x9e3779b97f4a7c15);
+	z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+	z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+	z ^ (z >> 31)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Go<const N: usize> {
+	pub a: [[u8; N]; N],
+	turn: bool,
+	// the player getting the komi
+	second: bool,
+	// half points
+	pub komi: i32,
+	passes: u8,
+	hash: u64,
+	// hashes of the boards so far, the last one is the current board
+	history: Vec<u64>,
+}
+
+pub type Go9 = Go<9>;
+
+impl<const N: usize> Go<N> {
+	pub fn with_komi(komi: i32, t: bool) -> Self {
+		assert!(N * N <= 192);
+		Go {
+			a: [[EMPTY; N]; N],
+			turn: t,
+			second: !t,
+			komi,
+			passes: 0,
+			hash: 0,
+			history: vec![0],
+		}
+	}
+	pub fn get(&self, p: usize) -> u8 {
+		self.a[p / N][p % N]
+	}
+	fn neighbours(p: usize) -> impl Iterator<Item = usize> {
+		let (x, y) = (p % N, p / N);
+		IntoIterator::into_iter([
+			(x > 0).then(|| p - 1),
+			(x + 1 < N).then(|| p + 1),
+			(y > 0).then(|| p - N),
+			(y + 1 < N).then(|| p + N),
+		])
+		.flatten()
+	}
+	// the stones of the group on p and whether it has a liberty
+	fn group(a: &[[u8; N]; N], p: usize) -> (Points, bool) {
+		let c = a[p / N][p % N];
+		let mut stones = [0; 3];
+		let mut free = false;
+		let mut stack = vec![p];
+		add(&mut stones, p);
+		while let Some(q) = stack.pop() {
+			for r in Self::neighbours(q) {
+				let rc = a[r / N][r % N];
+				if rc == EMPTY {
+					free = true;
+				} else if rc == c && !has(&stones, r) {
+					add(&mut stones, r);
+					stack.push(r);
+				}
+			}
+		}
+		(stones, free)
+	}
+	// the board and its hash after the player to move plays p, with the stones taken,
+	// None for suicide
+	fn play(&self, p: usize) -> Option<([[u8; N]; N], u64, Points)> {
+		let (own, opp) = if self.turn {
+			(TRUE, FALSE)
+		} else {
+			(FALSE, TRUE)
+		};
+		let mut a = self.a;
+		let mut hash = self.hash ^ key(p, own);
+		let mut taken = [0; 3];
+		a[p / N][p % N] = own;
+		for q in Self::neighbours(p) {
+			if a[q / N][q % N] != opp || has(&taken, q) {
+				continue;
+			}
+			let (stones, free) = Self::group(&a, q);
+			if !free {
+				for s in points(stones) {
+					a[s / N][s % N] = EMPTY;
+					hash ^= key(s, opp);
+					add(&mut taken, s);
+				}
+			}
+		}
+		if !Self::group(&a, p).1 {
+			return None;
+		}
+		Some((a, hash, taken))
+	}
+	fn legal(&self, p: usize) -> bool {
+		self.get(p) == EMPTY
+			&& self
+				.play(p)
+				.is_some_and(|(_, hash, _)| !self.history.contains(&hash))
+	}
+	// points of player true and of player false: stones and the empty regions
+	// that touch only their stones
+	pub fn area(&self) -> (i32, i32) {
+		let mut ans = (0, 0);
+		let mut seen = [0; 3];
+		for p in 0..N * N {
+			match self.get(p) {
+				TRUE => ans.0 += 1,
+				FALSE => ans.1 += 1,
+				_ if has(&seen, p) => {}
+				_ => {
+					let (mut size, mut touch) = (0, 0);
+					let mut stack = vec![p];
+					add(&mut seen, p);
+					while let Some(q) = stack.pop() {
+						size += 1;
+						for r in Self::neighbours(q) {
+							match self.get(r) {
+								EMPTY if !has(&seen, r) => {
+									add(&mut seen, r);
+									stack.push(r);
+								}
+								EMPTY => {}
+								c => touch |= c,
+							}
+						}
+					}
+					match touch {
+						TRUE => ans.0 += size,
+						FALSE => ans.1 += size,
+						_ => {}
+					}
+				}
+			}
+		}
+		ans
+	}
+	// area difference with the komi for player true, in half points
+	pub fn half_points(&self) -> i64 {
+		let (t, f) = self.area();
+		let komi = if self.second { self.komi } else { -self.komi };
+		(2 * (t - f) + komi) as i64
+	}
+	fn over(&self) -> bool {
+		self.passes >= 2 || self.history.len() > MAX_MOVES * N * N
+	}
+}
+
+impl<const N: usize> Game for Go<N> {
+	type M = u16; // y * N + x or PASS
+	type S = ([[u8; N]; N], bool);
+	type R = (u16, Points, u8);
+	fn new(t: bool) -> Self {
+		Self::with_komi(KOMI, t)
+	}
+	fn turn(&self) -> bool {
+		self.turn
+	}
+	fn get_moves(&self) -> Vec<u16> {
+		let mut ans: Vec<u16> = (0..N * N)
+			.filter(|p| self.legal(*p))
+			.map(|p| p as u16)
+			.collect();
+		ans.push(PASS);
+		ans
+	}
+	fn get_moves_sorted(&self) -> Vec<u16> {
+		self.get_moves()
+	}
+	fn get_static_state(&self) -> Self::S {
+		(self.a, self.turn)
+	}
+	fn state(&self) -> State {
+		if !self.over() {
+			return State::Going;
+		}
+		match self.half_points().cmp(&0) {
+			std::cmp::Ordering::Greater => State::Win,
+			std::cmp::Ordering::Less => State::Lose,
+			std::cmp::Ordering::Equal => State::Draw,
+		}
+	}
+	fn mov(&mut self, m: &u16) {
+		self.mov_with_rollback(m);
+	}
+	fn mov_with_rollback(&mut self, m: &u16) -> Self::R {
+		let passes = self.passes;
+		let mut taken = [0; 3];
+		if *m == PASS {
+			self.passes += 1;
+		} else {
+			(self.a, self.hash, taken) = self.play(*m as usize).unwrap();
+			self.passes = 0;
+		}
+		self.history.push(self.hash);
+		self.turn = !self.turn;
+		(*m, taken, passes)
+	}
+	fn rollback(&mut self, (m, taken, passes): Self::R) {
+		self.turn = !self.turn;
+		self.history.pop();
+		self.hash = *self.history.last().unwrap();
+		self.passes = passes;
+		if m != PASS {
+			let p = m as usize;
+			self.a[p / N][p % N] = EMPTY;
+			let opp = if self.turn { FALSE } else { TRUE };
+			for s in points(taken) {
+				self.a[s / N][s % N] = opp;
+			}
+		}
+	}
+	// in half points
+	fn score(&self) -> Option<i64> {
+		self.over().then(|| self.half_points())
+	}
+}
+
+impl<const N: usize> std::fmt::Display for Go<N> {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		for row in self.a.iter().rev() {
+			for c in row.iter() {
+				write!(
+					f,
+					"{}",
+					match *c {
+						TRUE => 'X',
+						FALSE => 'O',
+						_ => '.',
+					}
+				)?;
+			}
+			writeln!(f)?;
+		}
+		Ok(())
+	}
+}
@@ -0,0 +1,238 @@
+// Hex on N x N rhombus boards: player true joins the top and bottom rows, player
+// false the left and right columns, the board can't fill up without one of them
+// winning. Groups are kept in a union-find with the four edges as extra nodes,
+// by size and without path compression so that rollback can split them again.
+// With the swap rule the second player can take the first stone instead of moving,
+// it becomes theirs mirrored on the long diagonal.
+use crate::game::*;
+
+pub const EMPTY: u8 = 0;
+// stones of player true and of player false
+pub const TRUE: u8 = 1;
+pub const FALSE: u8 = 2;
+pub const SWAP: u16 = u16::MAX;
+// neighbours of (x, y)
+const DIRS: [(i32, i32); 6] = [(1, 0), (-1, 0), (0, 1), (0, -1), (1, -1), (-1, 1)];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Hex<const N: usize> {
+	pub a: [[u8; N]; N],
+	turn: bool,
+	pub swap: bool,
+	stones: usize,
+	state: State,
+	// N * N cells then top, bottom, left, right
+	parent: Vec<u16>,
+	size: Vec<u16>,
+}
+
+// the move and the roots it hung below others, or the swapped stone
+pub type HexRollback = (u16, [u16; 4]);
+
+impl<const N: usize> Hex<N> {
+	pub const TOP: usize = N * N;
+	pub const BOTTOM: usize = N * N + 1;
+	pub const LEFT: usize = N * N + 2;
+	pub const RIGHT: usize = N * N + 3;
+	pub fn with_swap(swap: bool, t: bool) -> Self {
+		Hex {
+			a: [[EMPTY; N]; N],
+			turn: t,
+			swap,
+			stones: 0,
+			state: State::Going,
+			parent: (0..(N * N + 4) as u16).collect(),
+			size: vec![1; N * N + 4],
+		}
+	}
+	pub fn get(&self, x: i32, y: i32) -> Option<u8> {
+		if x < 0 || y < 0 || x >= N as i32 || y >= N as i32 {
+			None
+		} else {
+			Some(self.a[y as usize][x as usize])
+		}
+	}
+	fn find(&self, mut p: usize) -> usize {
+		while self.parent[p] as usize != p {
+			p = self.parent[p] as usize;
+		}
+		p
+	}
+	// the root hung below the other, if they were apart
+	fn union(&mut self, p: usize, q: usize) -> Option<u16> {
+		let (mut p, mut q) = (self.find(p), self.find(q));
+		if p == q {
+			return None;
+		}
+		if self.size[p] > self.size[q] {
+			std::mem::swap(&mut p, &mut q);
+		}
+		self.parent[p] = q as u16;
+		self.size[q] += self.size[p];
+		Some(p as u16)
+	}
+	fn split(&mut self, p: u16) {
+		let q = self.parent[p as usize] as usize;
+		self.size[q] -= self.size[p as usize];
+		self.parent[p as usize] = p;
+	}
+	// puts a stone of color c on p, the roots hung below others, u16::MAX for none
+	fn place(&mut self, p: usize, c: u8) -> [u16; 4] {
+		let (x, y) = ((p % N) as i32, (p / N) as i32);
+		self.a[y as usize][x as usize] = c;
+		self.stones += 1;
+		let mut hung = [u16::MAX; 4];
+		let mut n = 0;
+		let mut link = |g: &mut Self, q: usize| {
+			if let Some(r) = g.union(p, q) {
+				hung[n] = r;
+				n += 1;
+			}
+		};
+		for (dx, dy) in DIRS {
+			if self.get(x + dx, y + dy) == Some(c) {
+				link(self, ((y + dy) as usize) * N + (x + dx) as usize);
+			}
+		}
+		if c == TRUE && y == 0 {
+			link(self, Self::TOP);
+		}
+		if c == TRUE && y == N as i32 - 1 {
+			link(self, Self::BOTTOM);
+		}
+		if c == FALSE && x == 0 {
+			link(self, Self::LEFT);
+		}
+		if c == FALSE && x == N as i32 - 1 {
+			link(self, Self::RIGHT);
+		}
+		hung
+	}
+	fn remove(&mut self, p: usize, hung: [u16; 4]) {
+		for r in hung.iter().rev().filter(|r| **r != u16::MAX) {
+			self.split(*r);
+		}
+		self.a[p / N][p % N] = EMPTY;
+		self.stones -= 1;
+	}
+	fn update_state(&mut self) {
+		self.state = if self.find(Self::TOP) == self.find(Self::BOTTOM) {
+			State::Win
+		} else if self.find(Self::LEFT) == self.find(Self::RIGHT) {
+			State::Lose
+		} else {
+			State::Going
+		};
+	}
+	// the first stone, the only one when SWAP can be played
+	fn first(&self) -> (usize, u8) {
+		let p = (0..N * N).find(|p| self.a[p / N][p % N] != EMPTY).unwrap();
+		(p, self.a[p / N][p % N])
+	}
+	// mirrors the only stone on the board to the other color
+	fn mirror(&mut self) {
+		let (p, c) = self.first();
+		// a single stone is only linked to edges
+		let hung = (0..N * N + 4)
+			.map(|r| r as u16)
+			.filter(|r| self.parent[*r as usize] != *r);
+		for r in hung.collect::<Vec<u16>>() {
+			self.split(r);
+		}
+		self.a[p / N][p % N] = EMPTY;
+		self.stones -= 1;
+		self.place((p % N) * N + p / N, TRUE + FALSE - c);
+	}
+}
+
+impl<const N: usize> Game for Hex<N> {
+	type M = u16; // y * N + x or SWAP
+	type S = ([[u8; N]; N], bool);
+	type R = HexRollback;
+	fn new(t: bool) -> Self {
+		Self::with_swap(false, t)
+	}
+	fn turn(&self) -> bool {
+		self.turn
+	}
+	fn get_moves(&self) -> Vec<u16> {
+		let mut ans = vec![];
+		if self.swap && self.stones == 1 && self.state == State::Going {
+			ans.push(SWAP);
+		}
+		for y in 0..N {
+			for x in 0..N {
+				if self.a[y][x] == EMPTY {
+					ans.push((y * N + x) as u16);
+				}
+			}
+		}
+		ans
+	}
+	fn get_moves_sorted(&self) -> Vec<u16> {
+		// the center first
+		let mut ans = self.get_moves();
+		ans.sort_by_key(|m| {
+			if *m == SWAP {
+				return 0;
+			}
+			let (x, y) = ((*m as usize % N) as i32, (*m as usize / N) as i32);
+			let c = N as i32 - 1;
+			(2 * x - c).abs() + (2 * y - c).abs() + (x + y - c).abs()
+		});
+		ans
+	}
+	fn get_static_state(&self) -> Self::S {
+		(self.a, self.turn)
+	}
+	fn state(&self) -> State {
+		self.state
+	}
+	fn mov(&mut self, m: &u16) {
+		self.mov_with_rollback(m);
+	}
+	fn mov_with_rollback(&mut self, m: &u16) -> Self::R {
+		let hung = if *m == SWAP {
+			self.mirror();
+			// after a swap the stone is the only one again
+			self.swap = false;
+			[u16::MAX; 4]
+		} else {
+			self.place(*m as usize, if self.turn { TRUE } else { FALSE })
+		};
+		self.update_state();
+		self.turn = !self.turn;
+		(*m, hung)
+	}
+	fn rollback(&mut self, (m, hung): Self::R) {
+		self.turn = !self.turn;
+		if m == SWAP {
+			self.mirror();
+			self.swap = true;
+		} else {
+			self.remove(m as usize, hung);
+		}
+		self.state = State::Going;
+	}
+}
+
+impl<const N: usize> std::fmt::Display for Hex<N> {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		for (y, row) in self.a.iter().enumerate() {
+			write!(f, "{}", " ".repeat(y))?;
+			for c in row.iter() {
+				write!(
+					f,
+					"{} ",
+					match *c {
+						TRUE => 'X',
+						FALSE => 'O',
+						_ => '.',
+					}
+				)?;
+			}
+			writeln!(f)?;
+		}
+		Ok(())
+	}
+}
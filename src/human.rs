@@ -0,0 +1,178 @@
+use crate::ai::Ai;
+use crate::game::*;
+use crate::notation::Notation;
+use std::io::BufRead;
+use std::io::Write;
+use std::time::Duration;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Command<M> {
+	Mov(M),
+	Undo,
+	Resign,
+}
+
+pub struct HumanAgent<G: Game> {
+	pub g: G,
+	input: Box<dyn BufRead>,
+	output: Box<dyn Write>,
+	history: Vec<G::R>,
+	// the side that resigned through get_mov
+	resigned: Option<bool>,
+}
+
+impl<G: Notation> HumanAgent<G> {
+	pub fn with_io<I: BufRead + 'static, O: Write + 'static>(t: bool, input: I, output: O) -> Self {
+		Self {
+			g: G::new(t),
+			input: Box::new(input),
+			output: Box::new(output),
+			history: vec![],
+			resigned: None,
+		}
+	}
+	// shows the board and asks until a legal move or a command is given
+	// None if the input is closed
+	pub fn read_command(&mut self) -> Option<Command<G::M>> {
+		let moves = self.g.get_moves();
+		let _ = writeln!(self.output, "{}", self.g);
+		let _ = writeln!(
+			self.output,
+			"moves: {}",
+			moves
+				.iter()
+				.map(|m| self.g.mov_to_string(m))
+				.collect::<Vec<String>>()
+				.join(" ")
+		);
+		loop {
+			let _ = write!(self.output, "> ");
+			let _ = self.output.flush();
+			let mut line = String::new();
+			if self.input.read_line(&mut line).ok()? == 0 {
+				return None;
+			}
+			match line.trim() {
+				"" => {}
+				"undo" => return Some(Command::Undo),
+				"resign" => return Some(Command::Resign),
+				s => match self.g.parse_mov(s) {
+					Some(m) if moves.contains(&m) => return Some(Command::Mov(m)),
+					Some(_) => {
+						let _ = writeln!(self.output, "illegal move: {}", s);
+					}
+					None => {
+						let _ = writeln!(self.output, "can't read move: {}", s);
+					}
+				},
+			}
+		}
+	}
+	// takes back the last move on the human's board, false if there is none
+	pub fn undo(&mut self) -> bool {
+		match self.history.pop() {
+			Some(rb) => {
+				self.g.rollback(rb);
+				true
+			}
+			None => false,
+		}
+	}
+}
+
+impl<G: Notation> Ai<G> for HumanAgent<G> {
+	fn new(t: bool) -> Self {
+		Self::with_io(
+			t,
+			std::io::BufReader::new(std::io::stdin()),
+			std::io::stdout(),
+		)
+	}
+	fn state(&self) -> State {
+		match self.resigned {
+			Some(true) => State::Lose,
+			Some(false) => State::Win,
+			None => self.g.state(),
+		}
+	}
+	fn print2game(&self) {
+		eprintln!("{}", self.g)
+	}
+	fn turn(&self) -> bool {
+		self.g.turn()
+	}
+	// undo needs the other player's help and only works in play_human. Resigning, or the
+	// input closing, makes state() the loss of the side to move. The move given back then
+	// is only a placeholder, drivers check state() after get_mov and don't play it
+	fn get_mov(&mut self, _tl: Duration) -> G::M {
+		if self.resigned.is_none() {
+			loop {
+				match self.read_command() {
+					Some(Command::Mov(m)) => return m,
+					Some(Command::Undo) => {
+						let _ = writeln!(self.output, "undo only works against play_human's engine");
+					}
+					Some(Command::Resign) | None => {
+						let _ = writeln!(self.output, "resigned");
+						self.resigned = Some(self.g.turn());
+						break;
+					}
+				}
+			}
+		}
+		self.g.get_moves()[0]
+	}
+	fn mov(&mut self, m: &G::M) {
+		self.history.push(self.g.mov_with_rollback(m));
+	}
+}
+
+// human plays side t against an A, with undo and resign
+// undo takes back moves until the human is to move again, the engine is rebuilt by replaying the game
+pub fn play_human<G: Notation, A: Ai<G>>(
+	human: &mut HumanAgent<G>,
+	t: bool,
+	tl: Duration,
+) -> State {
+	let t0 = human.g.turn();
+	let mut engine = A::new(t0);
+	let mut moves = Vec::<G::M>::new();
+	while human.state() == State::Going {
+		if human.turn() != t {
+			let m = engine.get_mov(tl);
+			let _ = writeln!(human.output, "engine plays {}", human.g.mov_to_string(&m));
+			engine.mov(&m);
+			human.mov(&m);
+			moves.push(m);
+			continue;
+		}
+		match human.read_command() {
+			Some(Command::Mov(m)) => {
+				engine.mov(&m);
+				human.mov(&m);
+				moves.push(m);
+			}
+			Some(Command::Undo) => {
+				let mut undone = false;
+				while !moves.is_empty() && (!undone || human.turn() != t) {
+					moves.pop();
+					human.undo();
+					undone |= human.turn() == t;
+				}
+				engine = A::new(t0);
+				for m in moves.iter() {
+					engine.mov(m);
+				}
+			}
+			Some(Command::Resign) | None => {
+				let _ = writeln!(human.output, "resigned");
+				return if t { State::Lose } else { State::Win };
+			}
+		}
+	}
+	let _ = writeln!(human.output, "{}\n{:?}", human.g, human.state());
+	if let Some(s) = human.g.score() {
+		let _ = writeln!(human.output, "score {}", s);
+	}
+	human.state()
+}
@@ -2,9 +2,19 @@
 extern crate test;
 
 pub mod ai;
+pub mod amazons;
+pub mod breakthrough;
+pub mod checkers;
+pub mod connect4;
 pub mod default_heuristic;
+pub mod dots_and_boxes;
+pub mod evolution;
+pub mod feature_heuristic;
 pub mod game;
+pub mod go;
 pub mod heuristic;
+pub mod hex;
+pub mod human;
 pub mod mancala;
 pub mod minimax_final;
 pub mod minimax_fixed;
@@ -12,17 +22,33 @@ pub mod minimax_hard;
 pub mod minimax_killer;
 pub mod minimax_killer_b;
 pub mod minimax_simple;
+pub mod mnk;
 pub mod monte_carlo_total;
 pub mod monte_carlo_tree_search;
+pub mod nim;
+pub mod notation;
+pub mod old_othello;
 pub mod old_tablut;
+pub mod old_tictactoe;
 pub mod othello;
+pub mod othello_endgame;
+pub mod oware;
+pub mod protocol;
 pub mod random_agent;
+pub mod referee;
+pub mod registry;
 pub mod tablut;
+pub mod tablut_client;
 pub mod tablut_heuristics;
-pub mod tictactoe;
+pub mod tafl;
+pub mod tuning;
+pub mod ultimate_tictactoe;
+// the old 3x3 board under its old path, mnk::Tictactoe replaces it
+pub use old_tictactoe as tictactoe;
 
 use crate::ai::*;
 use crate::game::*;
+use crate::registry::Registered;
 use rand::prelude::SliceRandom;
 use rand::*;
 use rand_xoshiro::Xoroshiro128Plus;
@@ -37,12 +63,29 @@ fn random_play<G: Game>() -> (State, usize) {
 	while g.state() == State::Going {
 		let moves = g.get_moves();
 		let m = moves.choose(&mut rng).unwrap();
-		g.mov(&m);
+		g.mov(m);
 		nmovs += 1;
 	}
 	(g.state(), nmovs)
 }
 
+// number of positions reached after depth moves, through mov_with_rollback and rollback
+pub fn perft<G: Game>(g: &mut G, depth: u32) -> u64 {
+	if depth == 0 {
+		return 1;
+	}
+	if g.state() != State::Going {
+		return 0;
+	}
+	let mut ans = 0;
+	for m in g.get_moves() {
+		let rb = g.mov_with_rollback(&m);
+		ans += perft(g, depth - 1);
+		g.rollback(rb);
+	}
+	ans
+}
+
 pub fn print_stats<G: Game>() {
 	let mut nw = 0;
 	let mut nl = 0;
@@ -78,27 +121,53 @@ pub fn print_stats<G: Game>() {
 }
 
 pub fn compete<G: Game + Display, A: Ai<G>, B: Ai<G>>(tl: Duration) {
-	eprintln!(
-		"Start {} vs {} in {}",
+	let mut a = A::new(true);
+	let mut b = B::new(true);
+	compete_dyn(
+		&mut a,
 		std::any::type_name::<A>(),
+		&mut b,
 		std::any::type_name::<B>(),
-		std::any::type_name::<G>()
+		tl,
 	);
-	let mut a = A::new(true);
-	let mut b = B::new(true);
+}
+
+// like compete, but with agents picked by name from the game's registry
+// returns None if one of the names is not registered
+pub fn compete_named<G: Registered + Display>(a: &str, b: &str, tl: Duration) -> Option<State> {
+	let registry = G::registry();
+	let mut aa = registry.create(a, true)?;
+	let mut bb = registry.create(b, true)?;
+	Some(compete_dyn(aa.as_mut(), a, bb.as_mut(), b, tl))
+}
+
+pub fn compete_dyn<G: Game + Display>(
+	a: &mut dyn Ai<G>,
+	an: &str,
+	b: &mut dyn Ai<G>,
+	bn: &str,
+	tl: Duration,
+) -> State {
+	eprintln!("Start {} vs {} in {}", an, bn, std::any::type_name::<G>());
 	let mut tta = Duration::ZERO;
 	let mut ttb = Duration::ZERO;
 	let mut mta = Duration::ZERO;
 	let mut mtb = Duration::ZERO;
 	let mut na = 0;
 	let mut nb = 0;
+	// the agents don't share their game, this one is for the score
+	let mut g = G::new(a.turn());
 	a.print2game();
-	while a.state() == State::Going {
+	while a.state() == State::Going && b.state() == State::Going {
 		let tts = Instant::now();
 		let m = match a.turn() {
 			true => a.get_mov(tl),
 			false => b.get_mov(tl),
 		};
+		// an agent that gave up in get_mov, like a resigning human, gives no real move
+		if a.state() != State::Going || b.state() != State::Going {
+			break;
+		}
 		if a.turn() {
 			na += 1;
 			let elapsed = tts.elapsed();
@@ -116,15 +185,21 @@ pub fn compete<G: Game + Display, A: Ai<G>, B: Ai<G>>(tl: Duration) {
 		}
 		a.mov(&m);
 		b.mov(&m);
+		g.mov(&m);
 		a.print2game();
 	}
-	if b.state() != a.state() {
+	let state = if a.state() != State::Going {
+		a.state()
+	} else {
+		b.state()
+	};
+	if g.state() != State::Going && b.state() != a.state() {
 		eprintln!("WTF STATES ARE DESYNCED HELP!!?");
 		eprintln!(
 			"{} state: {:?}\n{} state: {:?}",
-			std::any::type_name::<A>(),
+			an,
 			a.state(),
-			std::any::type_name::<B>(),
+			bn,
 			b.state()
 		);
 	}
@@ -134,50 +209,55 @@ pub fn compete<G: Game + Display, A: Ai<G>, B: Ai<G>>(tl: Duration) {
 	if nb == 0 {
 		nb = 1;
 	}
-	eprintln!(
-		"{} avg think time: {:?}",
-		std::any::type_name::<A>(),
-		tta / na
-	);
-	eprintln!("{} max think time: {:?}", std::any::type_name::<A>(), mta);
-	eprintln!(
-		"{} avg think time: {:?}",
-		std::any::type_name::<B>(),
-		ttb / nb
-	);
-	eprintln!("{} max think time: {:?}", std::any::type_name::<B>(), mtb);
-	eprintln!(
-		"{}\tvs\t{}",
-		std::any::type_name::<A>(),
-		std::any::type_name::<B>()
-	);
+	eprintln!("{} avg think time: {:?}", an, tta / na);
+	eprintln!("{} max think time: {:?}", an, mta);
+	eprintln!("{} avg think time: {:?}", bn, ttb / nb);
+	eprintln!("{} max think time: {:?}", bn, mtb);
+	eprintln!("{}\tvs\t{}", an, bn);
 	eprintln!(
 		"{}\t-\t{}",
-		if a.state() == State::Win { 1 } else { 0 },
-		if a.state() == State::Lose { 1 } else { 0 }
+		if state == State::Win { 1 } else { 0 },
+		if state == State::Lose { 1 } else { 0 }
 	);
+	if let Some(s) = g.score() {
+		eprintln!("score {}", s);
+	}
+	state
 }
 
 #[cfg(test)]
 mod tests {
 	use crate::ai::*;
+	use crate::amazons::*;
+	use crate::breakthrough::*;
+	use crate::checkers::*;
+	use crate::connect4::*;
 	use crate::default_heuristic::*;
+	use crate::dots_and_boxes::*;
+	use crate::evolution::*;
+	use crate::feature_heuristic::*;
 	use crate::game::*;
+	use crate::go::*;
+	use crate::heuristic::*;
+	use crate::hex::*;
+	use crate::human::*;
 	use crate::mancala::*;
-	use crate::minimax_final::*;
-	use crate::minimax_fixed::*;
-	use crate::minimax_hard::*;
-	use crate::minimax_killer::*;
 	use crate::minimax_killer_b::*;
-	use crate::minimax_simple::*;
-	use crate::monte_carlo_total::*;
+	use crate::mnk::*;
 	use crate::monte_carlo_tree_search::*;
+	use crate::nim::*;
+	use crate::notation::*;
 	use crate::othello::*;
+	use crate::oware::*;
 	use crate::random_agent::*;
+	use crate::registry::*;
 	use crate::tablut::*;
 	use crate::tablut_heuristics::*;
-	use crate::tictactoe::*;
+	use crate::tafl::*;
+	use crate::tuning::*;
+	use crate::ultimate_tictactoe::*;
 	use crate::*;
+	use std::collections::HashMap;
 	use std::time::Duration;
 
 	fn test_rollback<G: Game, A: Ai<G>, B: Ai<G>>() {
@@ -196,8 +276,7 @@ mod tests {
 			b.mov(&m);
 			v.push((g.clone(), rb));
 		}
-		while !v.is_empty() {
-			let x = v.pop().unwrap();
+		while let Some(x) = v.pop() {
 			assert_eq!(x.0.get_static_state(), g.get_static_state());
 			if !v.is_empty() {
 				g.rollback(x.1);
@@ -218,6 +297,1544 @@ mod tests {
 		test_rollback_game::<Tictactoe>();
 		test_rollback_game::<Tablut>();
 		test_rollback_game::<Othello>();
+		test_rollback_game::<Connect4>();
+		test_rollback_game::<Brandubh>();
+		test_rollback_game::<Copenhagen>();
+		test_rollback_game::<Oware>();
+		test_rollback_game::<Gomoku>();
+		test_rollback_game::<UltimateTictactoe>();
+		test_rollback_game::<Go9>();
+		test_rollback_game::<Go<7>>();
+		test_rollback_game::<Hex<11>>();
+		test_rollback_game::<Breakthrough>();
+		test_rollback_game::<Amazons>();
+		test_rollback_game::<Checkers>();
+		test_rollback_game::<Nim<3>>();
+		test_rollback_game::<Subtraction>();
+		test_rollback_game::<DotsAndBoxes<3, 3>>();
+	}
+
+	#[test]
+	fn mancala_test() {
+		static SIX: MancalaRules = MancalaRules {
+			seeds: 6,
+			empty_capture: false,
+			sweep: Sweep::Nobody,
+		};
+		assert_eq!(Mancala::new(true), Mancala::with_rules(&KALAH, true));
+		let g = Mancala::with_rules(&SIX, true);
+		assert_eq!(g.a.iter().map(|x| *x as u32).sum::<u32>(), 72);
+		// the last seed in an empty pit of the mover takes the opposite one, if the rules say so
+		let mut a = [3; 14];
+		a[4] = 1;
+		a[5] = 0;
+		a[6] = 0;
+		a[13] = 0;
+		for (rules, five, seven) in [(&KALAH, 4, 0), (&SIX, 1, 3)] {
+			let mut g = Mancala::with_rules(rules, true);
+			g.a = a;
+			g.mov(&4);
+			assert_eq!((g.a[5], g.a[7]), (five, seven));
+			assert!(!g.turn());
+		}
+		// the seeds left count for their owner or for nobody
+		let mut a = [0; 14];
+		a[5] = 1;
+		a[6] = 20;
+		a[7] = 4;
+		a[13] = 18;
+		let mut g = Mancala::with_rules(&KALAH, true);
+		g.a = a;
+		g.mov(&5);
+		assert_eq!(g.state(), State::Lose);
+		let mut g = Mancala::with_rules(&SIX, true);
+		g.a = a;
+		g.mov(&5);
+		assert_eq!(g.state(), State::Win);
+		// more than half of the seeds ends the game
+		g.a = [1; 14];
+		g.a[6] = 37;
+		assert_eq!(g.state(), State::Win);
+		g.a[6] = 36;
+		assert_eq!(g.state(), State::Going);
+		// rollbacks with the other rules
+		let mut rng = Xoroshiro128Plus::seed_from_u64(0);
+		for _ in 0..100 {
+			let mut g = Mancala::with_rules(&SIX, true);
+			let mut v = vec![];
+			while g.state() == State::Going {
+				let m = *g.get_moves().choose(&mut rng).unwrap();
+				v.push((g, g.mov_with_rollback(&m)));
+			}
+			while let Some((old, rb)) = v.pop() {
+				g.rollback(rb);
+				assert_eq!(g, old);
+			}
+		}
+	}
+
+	#[test]
+	fn oware_test() {
+		let mut g = Oware::new(true);
+		assert_eq!(perft(&mut g, 1), 6);
+		assert_eq!(perft(&mut g, 2), 36);
+		// 2 and 3 are taken going backwards from the last seed
+		let mut g = Oware::new(true);
+		g.a = [0, 0, 0, 0, 0, 2, 1, 2, 0, 3, 4, 4];
+		g.mov(&5);
+		assert_eq!(g.store, [5, 0]);
+		assert_eq!(g.a, [0, 0, 0, 0, 0, 0, 0, 0, 0, 3, 4, 4]);
+		// not when that takes everything
+		let mut g = Oware::new(true);
+		g.a = [0, 0, 0, 0, 0, 2, 1, 2, 0, 0, 0, 0];
+		g.mov(&5);
+		assert_eq!(g.store, [0, 0]);
+		// the origin house is left out
+		let mut g = Oware::new(false);
+		g.a = [1, 1, 1, 1, 1, 1, 12, 0, 0, 0, 0, 0];
+		g.mov(&6);
+		assert_eq!(g.a, [2, 2, 2, 2, 2, 2, 0, 2, 1, 1, 1, 1]);
+		// a starving opponent must be fed, if that can't be done the game ends
+		let mut g = Oware::new(true);
+		g.a = [1, 0, 0, 0, 2, 1, 0, 0, 0, 0, 0, 0];
+		g.store = [20, 24];
+		assert_eq!(g.get_moves(), vec![5, 4]);
+		g.a = [1, 1, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0];
+		g.store = [21, 24];
+		assert!(g.get_moves().is_empty());
+		assert_eq!(g.state(), State::Draw);
+		// endless cycles finish
+		let mut rng = Xoroshiro128Plus::seed_from_u64(0);
+		for _ in 0..100 {
+			let mut g = Oware::new(true);
+			let mut n = 0;
+			while g.state() == State::Going {
+				let m = *g.get_moves().choose(&mut rng).unwrap();
+				g.mov(&m);
+				n += 1;
+				assert!(n < 10000);
+			}
+		}
+	}
+
+	#[test]
+	fn mnk_test() {
+		// tictactoe as 3,3,3 against the old one
+		for t in [true, false] {
+			let mut g = Tictactoe::new(t);
+			let mut old = old_tictactoe::Tictactoe::new(t);
+			for d in 1..=9 {
+				assert_eq!(perft(&mut g, d), perft(&mut old, d));
+			}
+		}
+		// the old one still plays through its old path
+		let mut old = tictactoe::Tictactoe::new(true);
+		let m = old.parse_mov("b2").unwrap();
+		assert_eq!(old.mov_to_string(&m), "b2");
+		old.mov(&m);
+		let mut a = minimax_final::MinimaxFinal::<tictactoe::Tictactoe, DefaultHeuristic>::new(true);
+		a.mov(&m);
+		assert!(old
+			.get_moves()
+			.contains(&a.get_mov(Duration::from_millis(10))));
+		let res =
+			compete_named::<tictactoe::Tictactoe>("minimax_fixed:default:6", "random", Duration::ZERO);
+		assert!(res.is_some());
+		let p = |x: u16, y: u16| y * 15 + x;
+		let play = |g: &mut Gomoku, moves: &[u16]| {
+			for m in moves {
+				assert!(g.get_moves().contains(m), "{}\n{}", m, g);
+				g.mov(m);
+			}
+		};
+		// five on the diagonal, six wins only in freestyle
+		let mut g = Gomoku::new(false);
+		play(
+			&mut g,
+			&[p(3, 3), p(0, 0), p(4, 4), p(0, 1), p(5, 5), p(0, 2)],
+		);
+		play(&mut g, &[p(6, 6), p(0, 3), p(7, 7)]);
+		assert_eq!(g.state(), State::Lose);
+		static EXACT: MnkRules = MnkRules {
+			exact: true,
+			swap2: false,
+			renju: false,
+		};
+		let six = [
+			p(1, 1),
+			p(14, 0),
+			p(2, 1),
+			p(14, 2),
+			p(4, 1),
+			p(14, 4),
+			p(5, 1),
+			p(14, 6),
+		];
+		for (rules, state) in [
+			(&FREESTYLE, Some(State::Win)),
+			(&EXACT, Some(State::Going)),
+			(&RENJU, None),
+		] {
+			let mut g = Gomoku::with_rules(rules, true);
+			play(&mut g, &six);
+			play(&mut g, &[p(6, 1), p(14, 8)]);
+			assert_eq!(g.get_moves().contains(&p(3, 1)), state.is_some());
+			if let Some(state) = state {
+				g.mov(&p(3, 1));
+				assert_eq!(g.state(), state);
+			}
+		}
+		// renju: no double three for black, unless it makes five
+		let mut g = Gomoku::with_rules(&RENJU, true);
+		play(
+			&mut g,
+			&[
+				p(7, 7),
+				p(0, 0),
+				p(8, 7),
+				p(0, 14),
+				p(6, 8),
+				p(14, 0),
+				p(6, 9),
+				p(14, 14),
+			],
+		);
+		assert!(g.forbidden(6, 7));
+		assert!(!g.get_moves().contains(&p(6, 7)));
+		assert!(g.get_moves().contains(&p(9, 7)));
+		let mut h = g;
+		play(&mut h, &[p(9, 7), p(1, 14)]);
+		assert!(!h.forbidden(10, 7));
+		// swap2: three stones, then white, swap or two more and the choice goes back
+		let mut g = Gomoku::with_rules(&STANDARD, true);
+		play(&mut g, &[p(7, 7), p(8, 8), p(7, 8)]);
+		assert_eq!((g.phase(), g.turn()), (Phase::Choose, false));
+		let mut h = g;
+		play(&mut h, &[mnk::SWAP]);
+		assert_eq!((h.phase(), h.black(), h.turn()), (Phase::Play, false, true));
+		assert_eq!(h.a[8][8], WHITE);
+		let mut h = g;
+		play(&mut h, &[p(1, 1) | MORE, p(2, 2)]);
+		assert_eq!((h.phase(), h.turn()), (Phase::Choose2, true));
+		assert_eq!((h.a[1][1], h.a[2][2]), (WHITE, BLACK));
+		let mut k = h;
+		play(&mut k, &[mnk::SWAP]);
+		assert_eq!((k.black(), k.turn()), (true, false));
+		play(&mut h, &[p(3, 3)]);
+		assert_eq!((h.black(), h.turn(), h.a[3][3]), (false, false, WHITE));
+		// rollbacks through the opening and the restrictions
+		let mut rng = Xoroshiro128Plus::seed_from_u64(0);
+		for rules in [&STANDARD, &RENJU] {
+			for _ in 0..10 {
+				let mut g = Gomoku::with_rules(rules, true);
+				let mut v = vec![];
+				while g.state() == State::Going {
+					let m = *g.get_moves().choose(&mut rng).unwrap();
+					v.push((g, g.mov_with_rollback(&m)));
+				}
+				while let Some((old, rb)) = v.pop() {
+					g.rollback(rb);
+					assert_eq!(g, old);
+				}
+			}
+		}
+		// the heuristic sees a four to complete
+		let mut g = Gomoku::new(true);
+		let four = [
+			p(7, 7),
+			p(0, 0),
+			p(8, 7),
+			p(0, 2),
+			p(9, 7),
+			p(0, 4),
+			p(10, 7),
+			p(0, 6),
+		];
+		play(&mut g, &four);
+		let e = DefaultHeuristic::eval(&g);
+		assert!(e > 1 << 12 && e < 30000, "{}", e);
+		// two fours of white, black to move blocks both of them on a2 but not an open four
+		let mut g = Gomoku::new(true);
+		for (x, y) in [
+			(0, 0),
+			(1, 0),
+			(3, 0),
+			(4, 0),
+			(2, 1),
+			(2, 2),
+			(2, 3),
+			(2, 4),
+		] {
+			g.a[y][x] = mnk::WHITE;
+		}
+		g.a[5][2] = mnk::BLACK;
+		let e = DefaultHeuristic::eval(&g);
+		assert!(e > -(1 << 12), "{}", e);
+		let mut g = Gomoku::new(true);
+		for x in 1..5 {
+			g.a[0][x] = mnk::WHITE;
+		}
+		let e = DefaultHeuristic::eval(&g);
+		assert!(e < -(1 << 12) && e > -30000, "{}", e);
+		let mut a = MinimaxKillerB::<Gomoku, DefaultHeuristic>::new(true);
+		for m in four {
+			a.mov(&m);
+		}
+		let m = a.get_mov(Duration::from_millis(200));
+		assert!(m == p(6, 7) || m == p(11, 7));
+	}
+
+	#[test]
+	fn ultimate_tictactoe_test() {
+		let mut g = UltimateTictactoe::new(true);
+		for (d, n) in [81, 720, 6336, 55080].iter().enumerate() {
+			assert_eq!(perft(&mut g, d as u32 + 1), *n);
+		}
+		// the square played picks the next board
+		g.mov(&g.parse_mov("e5").unwrap());
+		assert_eq!(g.next(), 4);
+		assert_eq!(
+			g.get_moves(),
+			(36..45).filter(|m| *m != 40).collect::<Vec<u8>>()
+		);
+		let mut rng = Xoroshiro128Plus::seed_from_u64(0);
+		let mut free = 0;
+		for _ in 0..200 {
+			let mut g = UltimateTictactoe::new(true);
+			while g.state() == State::Going {
+				let moves = g.get_moves();
+				let open = (0..9).filter(|i| g.closed() & (1 << i) == 0);
+				if g.next() == FREE {
+					// anywhere on the boards still open
+					let empty = open.map(|i| 9 - (g.board(i).0 | g.board(i).1).count_ones() as usize);
+					assert_eq!(moves.len(), empty.sum::<usize>());
+					free += 1;
+				} else {
+					assert!(moves.iter().all(|m| m / 9 == g.next()));
+				}
+				let m = *moves.choose(&mut rng).unwrap();
+				g.mov(&m);
+				assert_eq!(g.next() == FREE, g.closed() & (1 << (m % 9)) != 0);
+			}
+			for i in 0..9 {
+				let (x, o) = g.board(i);
+				assert_eq!(g.meta.0 & (1 << i) != 0, won(x));
+				assert_eq!(g.meta.1 & (1 << i) != 0, won(o) && !won(x));
+			}
+			match g.state() {
+				State::Win => assert!(won(g.meta.0)),
+				State::Lose => assert!(won(g.meta.1)),
+				_ => assert_eq!(g.closed(), 0x1ff),
+			}
+		}
+		assert!(free > 200);
+	}
+
+	#[test]
+	fn go_test() {
+		let mut g = Go9::new(true);
+		assert_eq!(perft(&mut g, 1), 82);
+		assert_eq!(perft(&mut g, 2), 81 * 81 + 82);
+		let play = |g: &mut Go9, moves: &[&str]| {
+			for s in moves {
+				let m = g.parse_mov(s).unwrap();
+				assert!(g.get_moves().contains(&m), "{}\n{}", s, g);
+				g.mov(&m);
+			}
+		};
+		let p = |g: &Go9, s: &str| g.parse_mov(s).unwrap();
+		// a capture, rolled back
+		play(&mut g, &["b1", "a1"]);
+		let before = g.clone();
+		let rb = g.mov_with_rollback(&p(&g, "a2"));
+		assert_eq!(g.get(0), go::EMPTY);
+		// taking back would be suicide
+		assert!(!g.get_moves().contains(&p(&g, "a1")));
+		g.rollback(rb);
+		assert_eq!(g, before);
+		// the ko can't be taken back right away
+		let mut g = Go9::new(true);
+		play(
+			&mut g,
+			&["b1", "c1", "a2", "d2", "b3", "c3", "h8", "b2", "c2"],
+		);
+		assert_eq!(g.get(p(&g, "b2") as usize), go::EMPTY);
+		assert!(!g.get_moves().contains(&p(&g, "b2")));
+		play(&mut g, &["h9", "h7"]);
+		assert!(g.get_moves().contains(&p(&g, "b2")));
+		// area scoring, the komi goes to the second player
+		let mut g = Go9::new(true);
+		play(&mut g, &["pass", "pass"]);
+		assert_eq!((g.state(), g.score()), (State::Lose, Some(-KOMI as i64)));
+		let mut g = Go9::with_komi(0, false);
+		play(&mut g, &["pass", "pass"]);
+		assert_eq!(g.state(), State::Draw);
+		let mut g = Go9::new(false);
+		play(&mut g, &["e5", "pass", "pass"]);
+		assert_eq!(g.score(), Some(-162 + KOMI as i64));
+		assert_eq!(g.area(), (0, 81));
+		let mut g = Go9::new(true);
+		play(
+			&mut g,
+			&["c1", "d1", "c2", "d2", "c3", "d3", "b3", "d4", "a3", "e4"],
+		);
+		assert_eq!(g.state(), State::Going);
+		assert_eq!(g.area(), (5 + 4, 5));
+	}
+
+	#[test]
+	fn hex_test() {
+		// who joins their edges, by flood fill
+		fn joined(g: &Hex<7>, c: u8) -> bool {
+			let on = |p: usize, e: usize| {
+				if c == hex::TRUE {
+					p / 7 == e
+				} else {
+					p % 7 == e
+				}
+			};
+			let mut seen = [false; 49];
+			let mut stack: Vec<usize> = (0..49)
+				.filter(|p| on(*p, 0) && g.a[p / 7][p % 7] == c)
+				.collect();
+			while let Some(p) = stack.pop() {
+				if seen[p] {
+					continue;
+				}
+				seen[p] = true;
+				if on(p, 6) {
+					return true;
+				}
+				for (dx, dy) in [(1, 0), (-1, 0), (0, 1), (0, -1), (1, -1), (-1, 1)] {
+					let (x, y) = ((p % 7) as i32 + dx, (p / 7) as i32 + dy);
+					if g.get(x, y) == Some(c) {
+						stack.push(y as usize * 7 + x as usize);
+					}
+				}
+			}
+			false
+		}
+		let mut rng = Xoroshiro128Plus::seed_from_u64(0);
+		for _ in 0..200 {
+			let mut g = Hex::<7>::with_swap(true, true);
+			let mut v = vec![];
+			while g.state() == State::Going {
+				let m = *g.get_moves().choose(&mut rng).unwrap();
+				v.push((g.clone(), g.mov_with_rollback(&m)));
+				let state = match (joined(&g, hex::TRUE), joined(&g, hex::FALSE)) {
+					(true, false) => State::Win,
+					(false, true) => State::Lose,
+					(false, false) => State::Going,
+					_ => unreachable!(),
+				};
+				assert_eq!(g.state(), state, "\n{}", g);
+			}
+			while let Some((old, rb)) = v.pop() {
+				g.rollback(rb);
+				assert_eq!(g, old);
+			}
+		}
+		// the swapped stone changes color and side of the diagonal
+		let mut g = Hex::<5>::with_swap(true, true);
+		g.mov(&g.parse_mov("b1").unwrap());
+		assert!(g.get_moves().contains(&hex::SWAP));
+		g.mov(&hex::SWAP);
+		assert_eq!(
+			(g.get(0, 1), g.get(1, 0), g.turn()),
+			(Some(hex::FALSE), Some(hex::EMPTY), true)
+		);
+		assert!(!g.get_moves().contains(&hex::SWAP));
+		// the resistance sees who is closer to joining, the same way for both
+		let g = Hex::<5>::new(true);
+		assert!(DefaultHeuristic::eval(&g).abs() <= 1);
+		let mut g = Hex::<5>::new(true);
+		let mut h = Hex::<5>::new(true);
+		for (x, y) in [(2, 0), (2, 1), (2, 2), (2, 3)] {
+			g.a[y][x] = hex::TRUE;
+			h.a[x][y] = hex::FALSE;
+		}
+		let (eg, eh) = (DefaultHeuristic::eval(&g), DefaultHeuristic::eval(&h));
+		assert!(eg > 256 && (eg + eh).abs() <= 1, "{} {}", eg, eh);
+		// an empty cell walled in by player false carries no current for player true
+		let mut g = Hex::<5>::new(true);
+		for (x, y) in [(0, 2), (1, 2), (3, 2), (2, 1), (3, 1), (2, 3), (1, 3)] {
+			g.a[y][x] = hex::FALSE;
+		}
+		let e = DefaultHeuristic::eval(&g);
+		assert!(e < -256, "{}", e);
+		// one move from joining
+		let mut a = MonteCarloTreeSearch::<Hex<4>>::new(true);
+		for s in ["b1", "d1", "b2", "d2", "b3", "d3"] {
+			let m = a.g.parse_mov(s).unwrap();
+			a.mov(&m);
+		}
+		let m = a.get_mov(Duration::from_millis(200));
+		assert!(["a4", "b4"].contains(&a.g.mov_to_string(&m).as_str()));
+	}
+
+	#[test]
+	fn breakthrough_test() {
+		for t in [true, false] {
+			let mut g = Breakthrough::new(t);
+			for (d, n) in [22, 484, 11132, 256036].iter().enumerate() {
+				assert_eq!(perft(&mut g, d as u32 + 1), *n);
+			}
+		}
+		let mut rng = Xoroshiro128Plus::seed_from_u64(0);
+		for _ in 0..100 {
+			let mut g = Breakthrough::new(true);
+			while g.state() == State::Going {
+				let moves = g.get_moves();
+				let mut sorted = g.get_moves_sorted();
+				sorted.sort();
+				let mut all = moves.clone();
+				all.sort();
+				assert_eq!(all, sorted);
+				let m = moves[rng.gen_range(0..moves.len())];
+				// straight onto an empty square or diagonal
+				let (own, opp) = if g.turn() {
+					g.board
+				} else {
+					(g.board.1, g.board.0)
+				};
+				let d = m.1 as i32 - m.0 as i32;
+				assert!([7, 8, 9].contains(&d.abs()));
+				assert_eq!(d > 0, g.turn());
+				assert!(own & (1 << m.0) != 0 && own & (1 << m.1) == 0);
+				assert!(d.abs() != 8 || opp & (1 << m.1) == 0);
+				g.mov(&m);
+			}
+			let (t, f) = g.board;
+			assert_eq!(
+				g.state() == State::Win,
+				t & breakthrough::ROW_8 != 0 || f == 0 || (!g.turn() && g.get_moves().is_empty())
+			);
+		}
+		// a pawn on the 7th row to move wins
+		let mut g = Breakthrough::new(true);
+		g.board = (1 << 48, 1 << 63);
+		assert_eq!(DefaultHeuristic::eval(&g), 1 << 19);
+		let mut a = MinimaxKillerB::<Breakthrough, DefaultHeuristic>::new(true);
+		a.g = g;
+		let m = a.get_mov(Duration::from_millis(100));
+		g.mov(&m);
+		assert_eq!(g.state(), State::Win);
+	}
+
+	#[test]
+	fn checkers_test() {
+		for t in [true, false] {
+			let mut g = Checkers::new(t);
+			for (d, n) in [7, 49, 302, 1469, 7361, 36768, 179740].iter().enumerate() {
+				assert_eq!(perft(&mut g, d as u32 + 1), *n);
+			}
+		}
+		// a double jump is one move and has to be taken
+		let mut g = Checkers::new(true);
+		g.pieces = (1 << 4, (1 << 8) | (1 << 17) | (1 << 31));
+		assert_eq!(g.get_moves(), vec![(4, 22, (1 << 8) | (1 << 17))]);
+		assert_eq!(g.mov_to_string(&g.get_moves()[0]), "5x14x23");
+		g.mov(&g.get_moves()[0]);
+		assert_eq!(g.pieces, (1 << 22, 1 << 31));
+		// crowning ends the move even with a jump left for the king
+		let mut g = Checkers::new(true);
+		g.pieces = (1 << 20, (1 << 24) | (1 << 25) | (1 << 31));
+		assert_eq!(g.get_moves(), vec![(20, 29, 1 << 24)]);
+		g.mov(&g.get_moves()[0]);
+		assert_eq!(g.kings, 1 << 29);
+		// and next time the king goes on
+		g.mov(&(31, 27, 0));
+		assert_eq!(g.get_moves(), vec![(29, 22, 1 << 25)]);
+		// kings go both ways, and only kings moving makes a draw
+		let mut g = Checkers::new(true);
+		g.pieces = (1 << 13, 1 << 30);
+		g.kings = (1 << 13) | (1 << 30);
+		assert_eq!(g.get_moves().len(), 4);
+		let mut n = 0;
+		while g.state() == State::Going {
+			let m = if g.turn() {
+				*g.get_moves()
+					.iter()
+					.find(|m| m.1 == 17 || m.1 == 13)
+					.unwrap()
+			} else {
+				*g.get_moves()
+					.iter()
+					.find(|m| m.1 == 30 || m.1 == 26)
+					.unwrap()
+			};
+			g.mov(&m);
+			n += 1;
+		}
+		assert_eq!(
+			(g.state(), n),
+			(State::Draw, 2 * checkers::QUIET_MOVES as usize)
+		);
+		let mut rng = Xoroshiro128Plus::seed_from_u64(0);
+		for _ in 0..50 {
+			let mut g = Checkers::new(true);
+			while g.state() == State::Going {
+				let moves = g.get_moves();
+				assert_eq!(moves.len(), g.get_moves_sorted().len());
+				for m in moves.iter() {
+					assert_eq!(g.parse_mov(&g.mov_to_string(m)), Some(*m));
+				}
+				// men and kings never leave the dark squares
+				assert_eq!(g.pieces.0 & g.pieces.1, 0);
+				assert_eq!(g.kings & !(g.pieces.0 | g.pieces.1), 0);
+				g.mov(&moves[rng.gen_range(0..moves.len())]);
+			}
+		}
+	}
+
+	// every agent that can see to the end from random positions of G, checked against
+	// the theory: optimal tells whether a move keeps the best result, plies bounds the
+	// length of the rest of the game
+	fn test_optimal<G: Registered>(
+		optimal: impl Fn(&G, &G::M) -> bool,
+		plies: impl Fn(&G) -> u32,
+		positions: usize,
+	) {
+		let r = G::registry();
+		let mut rng = Xoroshiro128Plus::seed_from_u64(0);
+		let mut checked = 0;
+		while checked < positions {
+			let t = rng.gen();
+			let mut g = G::new(t);
+			let mut ms = vec![];
+			for _ in 0..rng.gen_range(3..10) {
+				let moves = g.get_moves();
+				if g.state() != State::Going {
+					break;
+				}
+				let m = moves[rng.gen_range(0..moves.len())];
+				g.mov(&m);
+				ms.push(m);
+			}
+			// only positions with a wrong move to make
+			if g.state() != State::Going || g.get_moves().iter().all(|m| optimal(&g, m)) {
+				continue;
+			}
+			checked += 1;
+			for name in r.names() {
+				// flat Monte Carlo doesn't converge to the best move, fixed depths don't
+				// reach the end and the tree search only gets there quickly on short endings
+				let fixed = name.rsplit(':').next().unwrap().parse::<u32>().ok();
+				if name == "random"
+					|| name == "monte_carlo_total"
+					|| fixed.is_some_and(|d| d < plies(&g))
+					|| (name == "mcts" && plies(&g) > 6)
+				{
+					continue;
+				}
+				let mut a = r.create(name, t).unwrap();
+				for m in ms.iter() {
+					a.mov(m);
+				}
+				let m = a.get_mov(Duration::from_millis(100));
+				assert!(optimal(&g, &m), "{} plays {:?} in {:?}", name, m, g);
+			}
+		}
+	}
+
+	// whether the player to move wins, when moving last wins
+	fn wins<G: Game>(g: &mut G) -> bool {
+		g.get_moves().iter().any(|m| {
+			let rb = g.mov_with_rollback(m);
+			let ans = g.state() != State::Going || !wins(g);
+			g.rollback(rb);
+			ans
+		})
+	}
+
+	// boxes the player to move gets from here on more than the other one, playing best
+	fn dots_value<const W: usize, const H: usize>(
+		g: &mut DotsAndBoxes<W, H>,
+		memo: &mut HashMap<u64, i64>,
+	) -> i64 {
+		if let Some(v) = memo.get(&g.lines) {
+			return *v;
+		}
+		let mut ans = i64::MIN;
+		for m in g.get_moves() {
+			let n = g.closes(m) as i64;
+			let rb = g.mov_with_rollback(&m);
+			let v = dots_value(g, memo);
+			g.rollback(rb);
+			ans = ans.max(if n > 0 { n + v } else { -v });
+		}
+		if ans == i64::MIN {
+			ans = 0;
+		}
+		memo.insert(g.lines, ans);
+		ans
+	}
+
+	// whether m wins, draws or loses like the best move of g on the 2x2 board
+	fn dots_optimal(g: &DotsAndBoxes<2, 2>, m: &u8, memo: &mut HashMap<u64, i64>) -> bool {
+		let mut value = |m: &u8| {
+			let mut h = *g;
+			let n = h.closes(*m) as i64;
+			h.mov(m);
+			let v = dots_value(&mut h, memo);
+			let (own, opp) = if g.turn() {
+				g.boxes
+			} else {
+				(g.boxes.1, g.boxes.0)
+			};
+			(own as i64 - opp as i64 + if n > 0 { n + v } else { -v }).signum()
+		};
+		let best = g.get_moves().iter().map(&mut value).max();
+		Some(value(m)) == best
+	}
+
+	#[test]
+	fn impartial_test() {
+		// Sprague-Grundy against plain search
+		for heaps in [[1, 2, 3], [1, 4, 5], [2, 2, 5], [3, 4, 5], [0, 3, 6]] {
+			let mut g = Nim::with_heaps(heaps, true);
+			assert_eq!(wins(&mut g), g.nim_sum() != 0, "{:?}", heaps);
+		}
+		for n in 0..30 {
+			assert_eq!(grundy(n, &SUBTRACT), [0, 1, 0, 1, 2, 3, 2][n as usize % 7]);
+			let mut g = Subtraction::with_takes(n, &SUBTRACT, false);
+			assert_eq!(wins(&mut g), g.grundy() != 0, "{}", n);
+		}
+		assert_eq!(perft(&mut Nim::<3>::new(true), 1), 12);
+		assert_eq!(Nim::<3>::with_heaps([0; 3], true).state(), State::Lose);
+		assert_eq!(
+			Subtraction::with_takes(0, &SUBTRACT, false).state(),
+			State::Win
+		);
+		// a winning move goes to a position of value 0
+		let after = |g: &Nim<3>, m: &(u8, u8)| {
+			let mut h = *g;
+			h.mov(m);
+			h
+		};
+		test_optimal::<Nim<3>>(
+			|g, m| g.nim_sum() == 0 || after(g, m).nim_sum() == 0,
+			|g| g.heaps.iter().map(|h| *h as u32).sum(),
+			8,
+		);
+		test_optimal::<Subtraction>(
+			|g, m| g.grundy() == 0 || grundy(g.heap - m, g.takes) == 0,
+			|g| g.heap as u32,
+			8,
+		);
+		// dots and boxes against an exact solve of the 2x2 board
+		let memo = std::cell::RefCell::new(HashMap::new());
+		test_optimal::<DotsAndBoxes<2, 2>>(
+			|g, m| dots_optimal(g, m, &mut memo.borrow_mut()),
+			|g| g.get_moves().len() as u32,
+			8,
+		);
+	}
+
+	#[test]
+	fn dots_and_boxes_test() {
+		type Dots2 = DotsAndBoxes<2, 2>;
+		assert_eq!(Dots2::LINES, 12);
+		assert_eq!(Dots5::LINES, 60);
+		// three sides of the bottom left box, the fourth one takes it and moves again
+		let mut g = Dots2::new(true);
+		for m in [
+			Dots2::horizontal(0, 0),
+			Dots2::vertical(0, 0),
+			Dots2::vertical(1, 0),
+		] {
+			assert_eq!(g.closes(m), 0);
+			g.mov(&m);
+		}
+		assert!(!g.turn());
+		let m = Dots2::horizontal(0, 1);
+		assert_eq!(g.closes(m), 1);
+		let rb = g.mov_with_rollback(&m);
+		assert_eq!((g.turn(), g.boxes), (false, (0, 1)));
+		g.rollback(rb);
+		assert_eq!((g.turn(), g.boxes), (false, (0, 0)));
+		// one line closing two boxes
+		let mut g = Dots2::new(true);
+		for x in 0..2 {
+			g.mov(&Dots2::horizontal(x, 0));
+			g.mov(&Dots2::horizontal(x, 1));
+		}
+		g.mov(&Dots2::vertical(0, 0));
+		g.mov(&Dots2::vertical(2, 0));
+		assert_eq!(g.closes(Dots2::vertical(1, 0)), 2);
+		// every agent against random play, taking boxes means moving twice in a row
+		type Dots3 = DotsAndBoxes<3, 3>;
+		let r = Dots3::registry();
+		for name in r.names() {
+			let mut a = r.create(name, true).unwrap();
+			let mut b = RandomAgent::<Dots3>::new(true);
+			let mut g = Dots3::new(true);
+			let mut again = 0;
+			while g.state() == State::Going {
+				let t = g.turn();
+				let m = if t {
+					a.get_mov(Duration::from_millis(10))
+				} else {
+					b.get_mov(Duration::from_millis(10))
+				};
+				assert!(g.get_moves().contains(&m), "{} plays {} in\n{}", name, m, g);
+				a.mov(&m);
+				b.mov(&m);
+				g.mov(&m);
+				again += (g.turn() == t && g.state() == State::Going) as usize;
+				assert_eq!((a.turn(), a.state()), (g.turn(), g.state()), "{}", name);
+			}
+			assert!(again > 0);
+			assert_eq!(g.score(), Some(g.boxes.0 as i64 - g.boxes.1 as i64));
+		}
+		// the minimax agents keep their search state through the extra turns of taking
+		// boxes: each one plays both sides to the end from a position with a box to take
+		let r = Dots2::registry();
+		let mut memo = HashMap::new();
+		let mut rng = Xoroshiro128Plus::seed_from_u64(0);
+		let mut checked = 0;
+		while checked < 4 {
+			let t = rng.gen();
+			let mut g = Dots2::new(t);
+			let mut ms = vec![];
+			while g.state() == State::Going && g.get_moves().iter().all(|m| g.closes(*m) == 0) {
+				let m = *g.get_moves().choose(&mut rng).unwrap();
+				g.mov(&m);
+				ms.push(m);
+			}
+			if g.state() != State::Going {
+				continue;
+			}
+			checked += 1;
+			for name in r
+				.names()
+				.filter(|x| x.starts_with("minimax") && !x.starts_with("minimax_fixed"))
+			{
+				let mut a = r.create(name, t).unwrap();
+				for m in ms.iter() {
+					a.mov(m);
+				}
+				let mut h = g;
+				while h.state() == State::Going {
+					let m = a.get_mov(Duration::from_millis(50));
+					assert!(
+						dots_optimal(&h, &m, &mut memo),
+						"{} plays {} in\n{}",
+						name,
+						m,
+						h
+					);
+					a.mov(&m);
+					h.mov(&m);
+				}
+			}
+		}
+	}
+
+	#[test]
+	fn amazons_test() {
+		let mut g = Amazons::new(true);
+		assert_eq!(perft(&mut g, 1), 2176);
+		let mut g = Amazons::new(false);
+		assert_eq!(perft(&mut g, 1), 2176);
+		let mut rng = Xoroshiro128Plus::seed_from_u64(0);
+		for _ in 0..5 {
+			let mut g = Amazons::new(true);
+			let mut n = 0;
+			while g.state() == State::Going {
+				let moves = g.get_moves();
+				assert_eq!(moves.len(), g.get_moves_sorted().len());
+				let m = moves[rng.gen_range(0..moves.len())];
+				let before = g.occupied();
+				g.mov(&m);
+				n += 1;
+				assert_eq!(g.occupied().count_ones(), before.count_ones() + 1);
+				assert_eq!(g.arrows.count_ones(), n);
+			}
+			assert!(g.get_moves().is_empty());
+		}
+		// a boxed in amazon can't move
+		let mut g = Amazons::new(true);
+		g.amazons = (1, 1 << 99);
+		g.arrows = (1 << 1) | (1 << 10) | (1 << 11);
+		assert_eq!(g.state(), State::Lose);
+		assert!(g.get_moves().is_empty());
+		g.arrows = (1 << 1) | (1 << 10);
+		assert_eq!(g.state(), State::Going);
+		// only along the diagonal, shooting back where it came from is fine
+		let moves = g.get_moves();
+		assert!(moves.iter().all(|m| m.0 == 0 && m.1 % 11 == 0 && m.1 < 99));
+		assert!(moves.contains(&(0, 11, 0)) && moves.contains(&(0, 88, 0)));
+		// thousands of moves at the root
+		let mut a = MonteCarloTreeSearch::<Amazons>::new(true);
+		let m = a.get_mov(Duration::from_millis(200));
+		assert!(Amazons::new(true).get_moves().contains(&m));
+	}
+
+	#[test]
+	fn othello_test() {
+		let mut g = Othello::new(true);
+		let mut old = old_othello::Othello::new(true);
+		for (d, n) in [4, 12, 56, 244, 1396, 8200].iter().enumerate() {
+			assert_eq!(perft(&mut g, d as u32 + 1), *n);
+			assert_eq!(perft(&mut old, d as u32 + 1), *n);
+		}
+		// random games against the old implementation, which ends on any two passes
+		let mut rng = Xoroshiro128Plus::from_rng(rand::thread_rng()).unwrap();
+		for _ in 0..1000 {
+			let mut g = Othello::new(true);
+			let mut old = old_othello::Othello::new(true);
+			while g.state() == State::Going && old.state() == State::Going {
+				assert_eq!(g.get_moves(), old.get_moves(), "\n{}", g);
+				for p in 0..64 {
+					assert_eq!(g.has_piece(p), old.has_piece(p));
+					assert_eq!(
+						g.has_piece(p) && g.get_piece(p),
+						old.has_piece(p) && old.get_piece(p)
+					);
+				}
+				let m = *g.get_moves().choose(&mut rng).unwrap();
+				g.mov(&m);
+				old.mov(&m);
+			}
+			// the old one counts player true's stones against 32 even with empty squares left
+			let (t, f) = g.stones();
+			if t | f == u64::MAX {
+				assert_eq!(g.state(), old.state());
+			} else if g.state() != State::Going {
+				assert_ne!(old.state(), State::Going);
+			}
+		}
+	}
+
+	#[test]
+	fn othello_endgame_test() {
+		// plain minimax on the final disc difference
+		fn exact(g: &mut Othello) -> i64 {
+			if let Some(s) = g.score() {
+				return s;
+			}
+			let t = g.turn();
+			let vals = g.get_moves().into_iter().map(|m| {
+				let rb = g.mov_with_rollback(&m);
+				let v = exact(g);
+				g.rollback(rb);
+				v
+			});
+			if t {
+				vals.max().unwrap()
+			} else {
+				vals.min().unwrap()
+			}
+		}
+		let mut rng = Xoroshiro128Plus::seed_from_u64(0);
+		let far = Instant::now() + Duration::from_secs(3600);
+		let mut solved = 0;
+		for _ in 0..20 {
+			let mut g = Othello::new(true);
+			let empties = |g: &Othello| (g.stones().0 | g.stones().1).count_zeros();
+			while g.state() == State::Going && empties(&g) > 8 {
+				let m = *g.get_moves().choose(&mut rng).unwrap();
+				g.mov(&m);
+			}
+			if g.state() != State::Going {
+				continue;
+			}
+			solved += 1;
+			let v = exact(&mut g);
+			let (m, sv) = othello_endgame::solve(&g, far).unwrap();
+			assert_eq!(sv, v, "\n{}", g);
+			let rb = g.mov_with_rollback(&m);
+			assert_eq!(exact(&mut g), v);
+			g.rollback(rb);
+			// agents play the solution
+			let mut a = MinimaxKillerB::<Othello, DefaultHeuristic>::new(true);
+			a.g = g;
+			let am = a.get_mov(Duration::from_secs(10));
+			let info = a.info().unwrap();
+			assert_eq!(info.score, v * 256);
+			assert_eq!((info.depth, info.nodes, info.pv), (0, 0, vec![am]));
+			let rb = g.mov_with_rollback(&am);
+			assert_eq!(exact(&mut g), v);
+			g.rollback(rb);
+		}
+		assert!(solved > 10);
+		// empty squares left at the end go to the winner
+		let mut early = 0;
+		for _ in 0..2000 {
+			let mut g = Othello::new(true);
+			while g.state() == State::Going {
+				assert_eq!(g.score(), None);
+				let m = *g.get_moves().choose(&mut rng).unwrap();
+				g.mov(&m);
+			}
+			let (t, f) = g.stones();
+			let (t, f) = (t.count_ones() as i64, f.count_ones() as i64);
+			let s = g.score().unwrap();
+			assert_eq!(
+				s.abs(),
+				if t == f {
+					0
+				} else {
+					(t - f).abs() + 64 - t - f
+				}
+			);
+			assert_eq!(s > 0, g.state() == State::Win);
+			assert_eq!(s < 0, g.state() == State::Lose);
+			early += (t + f < 64 && t != f) as i32;
+		}
+		assert!(early > 0);
+		// a lone corner and a full board can't be flipped, a lone side stone can
+		assert_eq!(stable(1, 0), 1);
+		assert_eq!(
+			stable(0x0f0f0f0f0f0f0f0f, 0xf0f0f0f0f0f0f0f0),
+			0x0f0f0f0f0f0f0f0f
+		);
+		assert_eq!(stable(2, 0), 0);
+		assert_eq!(stable(3, 0), 3);
+		// no time, no answer
+		let g = Othello::new(true);
+		assert_eq!(othello_endgame::solve(&g, Instant::now()), None);
+		assert_eq!(DefaultHeuristic::solve(&g, far), None);
+	}
+
+	#[test]
+	fn tafl_test() {
+		assert_eq!(perft(&mut Brandubh::new(true), 1), 24);
+		assert_eq!(perft(&mut Brandubh::new(false), 1), 40);
+		assert_eq!(perft(&mut Copenhagen::new(false), 1), 116);
+		fn play<V: Variant>(g: &mut Tafl<V>, moves: &[&str]) {
+			for s in moves {
+				let m = g.parse_mov(s).unwrap();
+				assert!(g.get_moves().contains(&m), "{} on\n{}", s, g);
+				g.mov(&m);
+			}
+		}
+		// custodial capture
+		let rows = [
+			".A.....", //
+			".D.....", "....A..", "...K...",
+		];
+		let mut g = Brandubh::with_position(&BRANDUBH, &rows, false);
+		play(&mut g, &["e3-b3"]);
+		assert_eq!(g.d.count(), 0);
+		// the copenhagen king needs four attackers
+		let rows = [
+			".....A.....",
+			"...........",
+			"...........",
+			"....AKA....",
+			".........A.",
+			"...........",
+			"...........",
+			"...........",
+			"...........",
+			"...........",
+			".D.........",
+		];
+		let mut g = Copenhagen::with_position(&COPENHAGEN, &rows, false);
+		play(&mut g, &["f1-f3", "b11-b10"]);
+		assert_eq!(g.state(), State::Going);
+		play(&mut g, &["j5-f5"]);
+		assert_eq!(g.state(), State::Lose);
+		// shieldwall
+		let rows = [
+			"...........",
+			"...........",
+			"...........",
+			"...........",
+			"...........",
+			".....K.....",
+			"...........",
+			"..A........",
+			"...........",
+			"...AA......",
+			"...DDA.....",
+		];
+		let mut g = Copenhagen::with_position(&COPENHAGEN, &rows, false);
+		play(&mut g, &["c8-c11"]);
+		assert_eq!(g.d.count(), 0);
+		// edge fort
+		let rows = [
+			".A.........",
+			"...........",
+			"...........",
+			"...........",
+			"...........",
+			"...........",
+			"...........",
+			".......D...",
+			"...........",
+			".....DD....",
+			"....DK.....",
+		];
+		let mut g = Copenhagen::with_position(&COPENHAGEN, &rows, true);
+		play(&mut g, &["h8-h11"]);
+		assert_eq!(g.state(), State::Win);
+		static NO_FORT: TaflRules = TaflRules {
+			edge_fort: false,
+			..COPENHAGEN
+		};
+		let mut g = Copenhagen::with_position(&NO_FORT, &rows, true);
+		play(&mut g, &["h8-h11"]);
+		assert_eq!(g.state(), State::Going);
+		// the empty throne takes defenders, d3 falls to an attacker on d2
+		static FORT: TaflRules = TaflRules {
+			edge_fort: true,
+			..BRANDUBH
+		};
+		let rows = [
+			"A......", //
+			".......", "..DDD..", ".D...D.", ".D...D.", ".D...D.", ".D.K.D.",
+		];
+		assert!(!Brandubh::with_position(&FORT, &rows, true).edge_fort());
+	}
+
+	#[test]
+	fn connect4_test() {
+		let mut g = Connect4::new(true);
+		let start = g;
+		for (d, n) in [7, 49, 343, 2401, 16807, 117649, 823536].iter().enumerate() {
+			assert_eq!(perft(&mut g, d as u32 + 1), *n);
+		}
+		assert_eq!(g, start);
+		// X wins on the diagonal
+		for m in [0, 1, 1, 2, 2, 3, 2, 3, 3, 6] {
+			g.mov(&m);
+		}
+		assert_eq!(g.state(), State::Going);
+		assert!(DefaultHeuristic::eval(&g) > 1000);
+		g.mov(&3);
+		assert_eq!(g.state(), State::Win);
+		g.rollback(3);
+		assert_eq!(g.state(), State::Going);
+		// a full board with no line
+		let mut g = Connect4::new(true);
+		for m in [
+			0, 1, 0, 1, 0, 1, 1, 0, 1, 0, 1, 0, 2, 3, 2, 3, 2, 3, 3, 2, 3, 2, 3, 2,
+		] {
+			g.mov(&m);
+		}
+		for m in [4, 5, 4, 5, 4, 5, 5, 4, 5, 4, 5, 4, 6, 6, 6, 6, 6, 6] {
+			assert_eq!(g.state(), State::Going);
+			g.mov(&m);
+		}
+		assert_eq!(g.state(), State::Draw);
+		assert!(g.get_moves_sorted().is_empty());
+	}
+
+	#[test]
+	fn registry_test() {
+		let tl = Duration::from_millis(30);
+		let r = Tictactoe::registry();
+		assert!(r.contains("minimax_final:default"));
+		assert!(r.create("minimax_final:nope", true).is_none());
+		for name in r.names() {
+			let mut a = r.create(name, true).unwrap();
+			assert!(a.turn());
+			let m = a.get_mov(tl);
+			assert!(Tictactoe::new(true).get_moves().contains(&m), "{}", name);
+		}
+		assert!(Tablut::registry().contains("minimax_killer_b:fm"));
+		assert_eq!(compete_named::<Tictactoe>("random", "nope", tl), None);
+		let res = compete_named::<Tictactoe>("minimax_fixed:default:6", "random", tl);
+		assert!(res.is_some() && res != Some(State::Going));
+	}
+
+	#[test]
+	fn human_test() {
+		let tl = Duration::from_millis(30);
+		let input = "b2\n".as_bytes();
+		let mut h = HumanAgent::<Tictactoe>::with_io(true, input, std::io::sink());
+		assert_eq!(h.get_mov(tl), 4);
+		// no panic when the input closes or the human resigns, the agent has lost
+		let input = "undo\n".as_bytes();
+		let mut h = HumanAgent::<Tictactoe>::with_io(true, input, std::io::sink());
+		assert!(Tictactoe::new(true).get_moves().contains(&h.get_mov(tl)));
+		assert_eq!(h.state(), State::Lose);
+		let input = "b2\nresign\n".as_bytes();
+		let mut h = HumanAgent::<Tictactoe>::with_io(true, input, std::io::sink());
+		h.mov(&0);
+		assert_eq!(h.get_mov(tl), 4);
+		h.mov(&4);
+		h.mov(&8);
+		h.get_mov(tl);
+		assert_eq!(h.state(), State::Win);
+		// a resigned player stops the game, its placeholder move isn't played
+		let mut a = RandomAgent::<Tictactoe>::new(true);
+		let mut h = HumanAgent::<Tictactoe>::with_io(true, "resign\n".as_bytes(), std::io::sink());
+		assert_eq!(
+			compete_dyn(&mut a, "random", &mut h, "human", tl),
+			State::Win
+		);
+		assert_eq!((h.g.get_moves().len(), h.g.turn()), (8, false));
+
+		let input = "zz\nd4\na1\nundo\n\nresign\n".as_bytes();
+		let mut h = HumanAgent::<Tictactoe>::with_io(true, input, std::io::sink());
+		let res = play_human::<Tictactoe, RandomAgent<Tictactoe>>(&mut h, true, tl);
+		assert_eq!(res, State::Lose);
+		assert_eq!(h.g, Tictactoe::new(true));
+
+		let g = Tablut::new(true);
+		for m in g.get_moves() {
+			assert_eq!(g.parse_mov(&g.mov_to_string(&m)), Some(m));
+		}
+		assert_eq!(g.parse_mov("e3-e1"), Some((38, 16)));
+		let g = Othello::new(true);
+		for m in g.get_moves() {
+			assert_eq!(g.parse_mov(&g.mov_to_string(&m)), Some(m));
+		}
+		let g = Mancala::new(false);
+		for m in g.get_moves() {
+			assert_eq!(g.parse_mov(&g.mov_to_string(&m)), Some(m));
+		}
+		let mut g = Gomoku::with_rules(&STANDARD, true);
+		for m in ["a1", "o15", "h8"] {
+			g.mov(&g.parse_mov(m).unwrap());
+		}
+		for m in g.get_moves() {
+			assert_eq!(g.parse_mov(&g.mov_to_string(&m)), Some(m));
+		}
+		assert_eq!(g.parse_mov("p1"), None);
+		let g = UltimateTictactoe::new(true);
+		for m in g.get_moves() {
+			assert_eq!(g.parse_mov(&g.mov_to_string(&m)), Some(m));
+		}
+		assert_eq!(g.parse_mov("e5"), Some(40));
+		let mut g = Hex::<11>::with_swap(true, true);
+		g.mov(&0);
+		for m in g.get_moves() {
+			assert_eq!(g.parse_mov(&g.mov_to_string(&m)), Some(m));
+		}
+		let g = Go9::new(true);
+		for m in g.get_moves() {
+			assert_eq!(g.parse_mov(&g.mov_to_string(&m)), Some(m));
+		}
+		let g = Breakthrough::new(false);
+		for m in g.get_moves() {
+			assert_eq!(g.parse_mov(&g.mov_to_string(&m)), Some(m));
+		}
+		assert_eq!(g.parse_mov("a7-b6"), Some((48, 41)));
+		let g = Checkers::new(true);
+		for m in g.get_moves() {
+			assert_eq!(g.parse_mov(&g.mov_to_string(&m)), Some(m));
+		}
+		assert_eq!(g.parse_mov("11-15"), Some((10, 14, 0)));
+		assert_eq!(g.parse_mov("11x15"), None);
+		let g = Dots5::new(true);
+		for m in g.get_moves() {
+			assert_eq!(g.parse_mov(&g.mov_to_string(&m)), Some(m));
+		}
+		assert_eq!(g.parse_mov("b1-a1"), Some(0));
+		assert_eq!(g.parse_mov("a1-b2"), None);
+		let g = Amazons::new(true);
+		for m in g.get_moves() {
+			assert_eq!(g.parse_mov(&g.mov_to_string(&m)), Some(m));
+		}
+		assert_eq!(g.parse_mov("d1-d7/g7"), Some((3, 63, 66)));
+		assert_eq!(g.parse_mov("d1-d7"), None);
+		for t in [true, false] {
+			let g = Oware::new(t);
+			for m in g.get_moves() {
+				assert_eq!(g.parse_mov(&g.mov_to_string(&m)), Some(m));
+			}
+		}
+	}
+
+	#[test]
+	fn feature_heuristic_test() {
+		// with the default weights it is FmHeuristic, apart from finished games
+		let mut rng = Xoroshiro128Plus::from_rng(rand::thread_rng()).unwrap();
+		let w = vec![1, 0, 0, 0, 0];
+		set_weights::<Tablut>(7, w.clone());
+		for _ in 0..20 {
+			let mut g = Tablut::new(true);
+			while g.state() == State::Going {
+				assert_eq!(FeatureHeuristic::<0>::eval(&g), FmHeuristic::eval(&g));
+				// only the defenders count in slot 7
+				assert_eq!(FeatureHeuristic::<7>::eval(&g), g.features()[0], "\n{}", g);
+				let m = *g.get_moves().choose(&mut rng).unwrap();
+				g.mov(&m);
+			}
+		}
+		// and follows weights set after it evaluated
+		let g = Tablut::new(true);
+		set_weights::<Tablut>(7, vec![0, 1, 0, 0, 0]);
+		assert_eq!(FeatureHeuristic::<7>::eval(&g), g.features()[1]);
+		set_weights::<Tablut>(7, w.clone());
+		assert_eq!(FeatureHeuristic::<7>::eval(&g), g.features()[0]);
+		assert_eq!(weights::<Tablut>(7), w);
+		assert_eq!(weights::<Tablut>(0), FM_WEIGHTS.to_vec());
+
+		// files
+		let w: Vec<i64> = (0..70).map(|x| x * 3 - 100).collect();
+		assert_eq!(
+			read_weights::<Othello>(&write_weights::<Othello>(&w)),
+			Ok(w)
+		);
+		let w =
+			read_weights::<Othello>("# tuned\n\ndiscs 5\n  corners\t-3 # why not\nh8 -2\n").unwrap();
+		assert_eq!(w[..7], [5, 8, -3, -24, 4, 16, 4]);
+		assert_eq!(w[69], -2);
+		assert!(read_weights::<Othello>("disks 5").is_err());
+		assert!(read_weights::<Othello>("discs").is_err());
+		assert!(read_weights::<Othello>("discs five").is_err());
+
+		// through the protocol, slot 0 of checkers is only changed here
+		let path = std::env::temp_dir().join(format!("weights_test_{}", std::process::id()));
+		std::fs::write(&path, "men 1\nkings 2\n").unwrap();
+		let input = format!(
+			"weights {}\ngame checkers\nagents\nweights\nweights {}\nweights {}.missing\nquit\n",
+			path.display(),
+			path.display(),
+			path.display()
+		);
+		let mut out = Vec::new();
+		protocol::run(std::io::Cursor::new(input), &mut out);
+		std::fs::remove_file(&path).unwrap();
+		let out = String::from_utf8(out).unwrap();
+		let lines: Vec<&str> = out.lines().collect();
+		assert_eq!(lines.len(), 4, "{}", out);
+		assert!(lines[0].starts_with("error"));
+		assert!(lines[1].contains(" minimax_final:feature "));
+		assert_eq!(lines[2], "error weights needs a file");
+		assert!(lines[3].starts_with("error can't read "));
+		assert_eq!(weights::<Checkers>(0), vec![1, 2, 8, 2]);
+	}
+
+	#[test]
+	fn tuning_test() {
+		// the first feature decides the result, the second is noise
+		let mut rng = Xoroshiro128Plus::seed_from_u64(0);
+		let data: Vec<(Vec<i64>, f64)> = (0..500)
+			.map(|_| {
+				let f = vec![rng.gen_range(-10..=10), rng.gen_range(-10..=10)];
+				let r = label(match f[0].cmp(&0) {
+					std::cmp::Ordering::Greater => State::Win,
+					std::cmp::Ordering::Less => State::Lose,
+					std::cmp::Ordering::Equal => State::Draw,
+				});
+				(f, r)
+			})
+			.collect();
+		let w = gradient_descent(&data, vec![0.0, 0.0], 10.0, 200);
+		assert!(w[0] > 10.0 * w[1].abs(), "{:?}", w);
+		let w = local_search(&data, vec![0, 50], 10.0, 16);
+		assert!(w[0] > 10 * w[1].abs(), "{:?}", w);
+
+		assert!(self_play::<Othello>("random", "nobody", 1, 0, Duration::ZERO, 0).is_none());
+		let data = self_play::<Othello>("random", "random", 40, 0, Duration::ZERO, 0).unwrap();
+		assert!(data.len() > 40 * 50);
+		assert!(data.iter().all(|(g, _)| g.state() == State::Going));
+		let mut start = vec![0; 70];
+		start[0] = 1;
+		set_weights::<Othello>(5, start.clone());
+		let (scale, before, after) = tune::<Othello, 5>(&data, 100);
+		assert!(after < before, "{} {}", before, after);
+		let w = weights::<Othello>(5);
+		assert_ne!(w, start);
+		assert!((loss::<Othello, FeatureHeuristic<5>>(&data, scale) - after).abs() < 1e-9);
+	}
+
+	#[test]
+	fn evolution_test() {
+		let start = <Mancala as Features>::WEIGHTS;
+		let p = Population::new(start, 6, 3, 42);
+		assert_eq!(p.members.len(), 6);
+		assert_eq!(p.members[0], start);
+		assert!(p.members[1..].iter().all(|w| w != start));
+		assert_eq!(Population::read(&p.write()), Ok(p.clone()));
+		assert!(Population::read("seed 1\ngeneration 0\n1 2\n3\n").is_err());
+		assert!(Population::read("generation 0\n1 2\n").is_err());
+
+		// stopping and resuming gives the same generations
+		let mut a = p.clone();
+		evolve::<Mancala, 2>(&mut a, 2, 4, 3, None).unwrap();
+		let mut b = p;
+		let scores = next_generation::<Mancala, 2>(&mut b, 4, 3);
+		// 15 pairs playing twice
+		assert_eq!(scores.iter().sum::<i64>(), 60);
+		assert!(scores.windows(2).all(|x| x[0] >= x[1]));
+		let path = std::env::temp_dir().join(format!("evolution_test_{}", std::process::id()));
+		let path = path.to_str().unwrap();
+		b.save(path).unwrap();
+		let mut b = Population::load(path).unwrap();
+		std::fs::remove_file(path).unwrap();
+		evolve::<Mancala, 2>(&mut b, 1, 4, 3, Some(path)).unwrap();
+		Population::load(path).unwrap();
+		std::fs::remove_file(path).unwrap();
+		assert_eq!(a, b);
+		assert_eq!(a.generation, 2);
+
+		// games at the same time don't play with each other's weights, nor with the slots'
+		let bad: Vec<i64> = start.iter().map(|x| -x).collect();
+		let alone = [
+			play::<Mancala, 2>(start, &bad, &[]),
+			play::<Mancala, 2>(&bad, start, &[]),
+		];
+		for slot in 1..3 {
+			set_weights::<Mancala>(slot, vec![0; 14]);
+		}
+		assert_eq!(play::<Mancala, 2>(start, &bad, &[]), alone[0]);
+		for slot in 1..3 {
+			set_weights::<Mancala>(slot, start.to_vec());
+		}
+		std::thread::scope(|s| {
+			let games: Vec<_> = [(start, &bad[..]), (&bad[..], start)]
+				.iter()
+				.map(|&(x, y)| {
+					s.spawn(move || {
+						(0..20)
+							.map(|_| play::<Mancala, 2>(x, y, &[]))
+							.collect::<Vec<_>>()
+					})
+				})
+				.collect();
+			for (h, r) in games.into_iter().zip(alone.iter()) {
+				assert!(h.join().unwrap().iter().all(|x| x == r));
+			}
+		});
+	}
+
+	#[test]
+	fn protocol_test() {
+		let input = "isready\nmove a1\ngame tictactoe\nagent minimax_fixed:default:6\n\
+			position startpos moves a1 b2\nmove zz\nmove a1\ngo movetime 50\nquit\n";
+		let mut out = Vec::new();
+		protocol::run(std::io::Cursor::new(input), &mut out);
+		let out = String::from_utf8(out).unwrap();
+		let lines: Vec<&str> = out.lines().collect();
+		assert_eq!(lines.len(), 6, "{}", out);
+		assert_eq!(lines[0], "readyok");
+		assert!(lines[1].starts_with("error"));
+		assert_eq!(lines[2], "error can't read move zz");
+		assert_eq!(lines[3], "error illegal move a1");
+		assert!(lines[4].starts_with("info depth 6 "));
+		let mut g = Tictactoe::new(true);
+		g.mov(&0);
+		g.mov(&4);
+		let m = g.parse_mov(lines[5].strip_prefix("bestmove ").unwrap());
+		assert!(g.get_moves().contains(&m.unwrap()));
+	}
+
+	#[test]
+	fn protocol_stop_test() {
+		use std::io::Write;
+		let (r, mut w) = std::io::pipe().unwrap();
+		let h = std::thread::spawn(move || {
+			let mut out = Vec::new();
+			protocol::run(std::io::BufReader::new(r), &mut out);
+			String::from_utf8(out).unwrap()
+		});
+		writeln!(
+			w,
+			"game tablut\nagent minimax_killer_b:default\ngo infinite"
+		)
+		.unwrap();
+		std::thread::sleep(Duration::from_millis(300));
+		writeln!(w, "stop\ngo ttime 100\nquit").unwrap();
+		let out = h.join().unwrap();
+		assert_eq!(out.lines().filter(|x| x.starts_with("bestmove")).count(), 2);
+	}
+
+	#[test]
+	fn protocol_early_stop_test() {
+		// a stop with nothing running doesn't cut the next go short, even in another game
+		let input = "stop\ngame othello\nstop\nagent minimax_killer_b:default\ngo movetime 300\nquit\n";
+		let mut out = Vec::new();
+		let st = std::time::Instant::now();
+		protocol::run(std::io::Cursor::new(input), &mut out);
+		assert!(
+			st.elapsed() > Duration::from_millis(200),
+			"{:?}",
+			st.elapsed()
+		);
+		let out = String::from_utf8(out).unwrap();
+		assert_eq!(out.lines().filter(|x| x.starts_with("bestmove")).count(), 1);
+	}
+
+	#[test]
+	fn tablut_client_test() {
+		use crate::tablut_client::*;
+		let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+		let addr = listener.local_addr().unwrap();
+		// mock server, client is white and the server plays black's first moves
+		let server = std::thread::spawn(move || {
+			let (mut s, _) = listener.accept().unwrap();
+			assert_eq!(read_string(&mut s).unwrap(), "\"tester\"");
+			let mut g = Tablut::new(true);
+			for i in 0..3 {
+				if i != 0 {
+					write_string(&mut s, &state_to_json(&g)).unwrap();
+					g.mov(&g.get_moves()[0]);
+				}
+				write_string(&mut s, &state_to_json(&g)).unwrap();
+				let m = mov_from_json(&read_string(&mut s).unwrap()).unwrap();
+				assert!(g.get_moves().contains(&m));
+				g.mov(&m);
+			}
+			g.state = State::Lose;
+			write_string(&mut s, &state_to_json(&g)).unwrap();
+			g
+		});
+		let mut a = MinimaxKillerB::<Tablut, FmHeuristic>::new(true);
+		let res = play(addr, "tester", true, &mut a, Duration::from_millis(600));
+		let g = server.join().unwrap();
+		assert_eq!(res.unwrap(), State::Lose);
+		// the client followed the server's moves
+		assert_eq!((a.g.a, a.g.d, a.g.k), (g.a, g.d, g.k));
+
+		let g = Tablut::new(true);
+		let (h, t) = state_from_json(&state_to_json(&g)).unwrap();
+		assert_eq!((h, t), (g, Some(true)));
+		assert_eq!(
+			mov_from_json(&mov_to_json(&(38, 16), true)).unwrap(),
+			(38, 16)
+		);
+	}
+
+	#[test]
+	fn referee_test() {
+		use crate::referee::*;
+		use std::io::{BufRead, Write};
+		let tl = Duration::from_millis(50);
+		let listener = listen(0).unwrap();
+		let addr = listener.local_addr().unwrap();
+		let a = std::thread::spawn(move || join(addr).unwrap());
+		let b = std::thread::spawn(move || join(addr).unwrap());
+		let rec = referee::<Tictactoe>(&listener, "tictactoe", true, tl).unwrap();
+		a.join().unwrap();
+		b.join().unwrap();
+		assert_eq!(rec.end, End::Finished);
+		let mut g = Tictactoe::new(true);
+		for m in rec.moves.iter() {
+			assert!(g.get_moves().contains(m));
+			g.mov(m);
+		}
+		assert_eq!(g.state(), rec.state);
+		assert_eq!(rec.score, None);
+
+		// a client answering nonsense loses, connecting first it plays player true
+		let s = std::net::TcpStream::connect(addr).unwrap();
+		let b = std::thread::spawn(move || {
+			let mut w = s.try_clone().unwrap();
+			for line in std::io::BufReader::new(s).lines() {
+				let line = line.unwrap();
+				if line.starts_with("go") {
+					writeln!(w, "bestmove z9").unwrap();
+				} else if line == "quit" {
+					break;
+				}
+			}
+		});
+		let a = std::thread::spawn(move || join(addr).unwrap());
+		let rec = referee::<Tictactoe>(&listener, "tictactoe", false, tl).unwrap();
+		a.join().unwrap();
+		b.join().unwrap();
+		assert_eq!(rec.end, End::IllegalMove(true));
+		assert_eq!(rec.moves.len(), 1);
+		assert_eq!(rec.state, State::Lose);
+		assert_eq!(rec.score, None);
 	}
 
 	#[test]
@@ -230,12 +1847,275 @@ mod tests {
 		);
 	}
 
+	// row y = 0 first, A D K for the pieces
+	fn tablut_board(rows: [&str; 9]) -> [[Tile; 9]; 9] {
+		rows.map(|r| {
+			let mut row = [Tile::E; 9];
+			for (x, c) in r.chars().enumerate() {
+				row[x] = match c {
+					'A' => Tile::A,
+					'D' => Tile::D,
+					'K' => Tile::K,
+					_ => Tile::E,
+				};
+			}
+			row
+		})
+	}
+
+	#[test]
+	fn victory_paths_test() {
+		// alone on c3 the king sees a3, c1, i3 and c9
+		let mut b = tablut_board([
+			".........",
+			".........",
+			"..K......",
+			".........",
+			".........",
+			".........",
+			".........",
+			".........",
+			".........",
+		]);
+		let g = Tablut::from_board(&b, true);
+		assert_eq!(victory_paths(&g).0, 4);
+		assert_eq!(VpHeuristic::eval(&g), i64::MAX - 1);
+		let g = Tablut::from_board(&b, false);
+		assert_eq!(VpHeuristic::eval(&g), FmHeuristic::eval(&g) + 10000);
+		b[2][1] = Tile::A;
+		b[5][2] = Tile::D;
+		let g = Tablut::from_board(&b, true);
+		assert_eq!(victory_paths(&g).0, 2);
+		// the camps block the way, from the throne nothing is in two moves
+		let g = Tablut::new(true);
+		assert_eq!(victory_paths(&g), (0, 0));
+		assert_eq!(VpHeuristic::eval(&g), FmHeuristic::eval(&g));
+
+		// the same as trying the king's moves, on random games
+		fn king_goals(b: &[[Tile; 9]; 9]) -> (i64, Vec<[[Tile; 9]; 9]>) {
+			let g = Tablut::from_board(b, true);
+			let kp = g.k.trailing_zeros() as u8;
+			let mut goals = 0;
+			let mut via = vec![];
+			for (_, q) in g.get_moves().into_iter().filter(|m| m.0 == kp) {
+				if g.goal() & (1 << q) != 0 {
+					goals += 1;
+				} else {
+					let mut b = *b;
+					let (x, y) = (q as usize % 11 - 1, q as usize / 11 - 1);
+					b[(kp as usize) / 11 - 1][kp as usize % 11 - 1] = Tile::E;
+					b[y][x] = Tile::K;
+					via.push(b);
+				}
+			}
+			(goals, via)
+		}
+		let mut rng = Xoroshiro128Plus::seed_from_u64(0);
+		let mut checked = 0;
+		for _ in 0..20 {
+			let mut g = Tablut::new(true);
+			while g.state() == State::Going {
+				let (one, via) = king_goals(&g.get_board());
+				let two = via.iter().map(|b| king_goals(b).0).sum();
+				assert_eq!(victory_paths(&g), (one, two), "\n{:?}", g.get_board());
+				checked += (two > 0) as i32;
+				let m = *g.get_moves().choose(&mut rng).unwrap();
+				g.mov(&m);
+			}
+		}
+		assert!(checked > 0);
+	}
+
+	#[test]
+	fn tablut_rules_test() {
+		assert_eq!(Tablut::new(true), Tablut::with_rules(&ASHTON, true));
+		let p = |x: u8, y: u8| y * 11 + x + 12;
+		// escape on the goal squares or on the corners only
+		let b = tablut_board([
+			".........",
+			".K.......",
+			".........",
+			".........",
+			".........",
+			".........",
+			"......A..",
+			".........",
+			".........",
+		]);
+		let mut g = Tablut::from_board(&b, true);
+		g.mov(&(p(1, 1), p(0, 1)));
+		assert_eq!(g.state(), State::Win);
+		let mut g = Tablut::from_board_with_rules(&b, &CORNER, true);
+		g.mov(&(p(1, 1), p(0, 1)));
+		assert_eq!(g.state(), State::Going);
+		g.mov(&(p(6, 6), p(6, 5)));
+		g.mov(&(p(0, 1), p(0, 0)));
+		assert_eq!(g.state(), State::Win);
+		// the king away from the throne is taken by two, or by four
+		let b = tablut_board([
+			".........",
+			".........",
+			".AK......",
+			".........",
+			".........",
+			"...A.....",
+			".........",
+			"........D",
+			".........",
+		]);
+		let mut g = Tablut::from_board(&b, false);
+		g.mov(&(p(3, 5), p(3, 2)));
+		assert_eq!(g.state(), State::Lose);
+		let mut g = Tablut::from_board_with_rules(&b, &SEABATTLE, false);
+		g.mov(&(p(3, 5), p(3, 2)));
+		assert_eq!(g.state(), State::Going);
+		// only an armed king captures
+		static UNARMED: TablutRules = TablutRules {
+			armed_king: false,
+			..ASHTON
+		};
+		let b = tablut_board([
+			"..D......",
+			"..A......",
+			".........",
+			".........",
+			".........",
+			"..K......",
+			".........",
+			"........A",
+			".........",
+		]);
+		let mut g = Tablut::from_board(&b, true);
+		g.mov(&(p(2, 5), p(2, 2)));
+		assert_eq!(g.a.count_ones(), 1);
+		let mut g = Tablut::from_board_with_rules(&b, &UNARMED, true);
+		g.mov(&(p(2, 5), p(2, 2)));
+		assert_eq!(g.a.count_ones(), 2);
+		// without the camps the throne takes defenders only while the king is off it
+		let b = tablut_board([
+			".........",
+			".........",
+			"......A..",
+			"....D....",
+			"....K....",
+			".........",
+			".........",
+			".........",
+			".........",
+		]);
+		for rules in [&SEABATTLE, &CORNER] {
+			let mut g = Tablut::from_board_with_rules(&b, rules, false);
+			g.mov(&(p(6, 2), p(4, 2)));
+			assert_eq!(g.d.count_ones(), 1);
+		}
+		let mut b = b;
+		b[4][4] = Tile::E;
+		b[6][1] = Tile::K;
+		let mut g = Tablut::from_board_with_rules(&b, &SEABATTLE, false);
+		g.mov(&(p(6, 2), p(4, 2)));
+		assert_eq!(g.d.count_ones(), 0);
+		// the camps only block with camps on
+		let b = tablut_board([
+			".........",
+			"..D......",
+			".........",
+			".........",
+			"....K....",
+			".........",
+			".........",
+			"........A",
+			".........",
+		]);
+		let m = (p(2, 1), p(6, 1));
+		assert!(!Tablut::from_board(&b, true).get_moves().contains(&m));
+		assert!(Tablut::from_board_with_rules(&b, &SEABATTLE, true)
+			.get_moves()
+			.contains(&m));
+		// repetitions, twice for ashton and three times for seabattle
+		let cycle = [
+			(p(2, 1), p(2, 2)),
+			(p(8, 7), p(8, 6)),
+			(p(2, 2), p(2, 1)),
+			(p(8, 6), p(8, 7)),
+		];
+		let mut g = Tablut::from_board(&b, true);
+		let mut rbs = vec![];
+		for m in cycle.iter() {
+			assert_eq!(g.state(), State::Going);
+			rbs.push(g.mov_with_rollback(m));
+		}
+		assert_eq!(g.state(), State::Draw);
+		assert_eq!(DefaultHeuristic::eval(&g), 0);
+		g.rollback(rbs.pop().unwrap());
+		assert_eq!(g.state(), State::Going);
+		assert_eq!(g.repetitions(), 1);
+		// the searches tell the board apart from the same one with no cycle behind it
+		let h = Tablut::from_board(&g.get_board(), g.turn());
+		assert_ne!(h.get_static_state(), g.get_static_state());
+		let mut h = Tablut::with_rules(&SEABATTLE, true);
+		h.clone_from(&g);
+		assert_eq!(h, g);
+		g.rollback(rbs.pop().unwrap());
+		g.rollback(rbs.pop().unwrap());
+		g.rollback(rbs.pop().unwrap());
+		assert_eq!(
+			g.get_static_state(),
+			Tablut::from_board(&b, true).get_static_state()
+		);
+		// while transpositions still meet, the defender and the king moved in either order
+		let orders = [
+			[(p(2, 1), p(2, 2)), (p(8, 7), p(8, 6)), (p(4, 4), p(4, 3))],
+			[(p(4, 4), p(4, 3)), (p(8, 7), p(8, 6)), (p(2, 1), p(2, 2))],
+		]
+		.map(|ms| {
+			let mut g = Tablut::from_board(&b, true);
+			for m in ms {
+				assert!(g.get_moves().contains(&m));
+				g.mov(&m);
+			}
+			g
+		});
+		assert_eq!(orders[0].get_static_state(), orders[1].get_static_state());
+		let mut g = Tablut::from_board_with_rules(&b, &SEABATTLE, true);
+		for m in cycle.iter().chain(cycle.iter()) {
+			assert_eq!(g.state(), State::Going);
+			g.mov(m);
+		}
+		assert_eq!(g.state(), State::Draw);
+		assert_eq!(g.repetitions(), 3);
+		// positions before a capture can't come back
+		let b = tablut_board([
+			".........",
+			"..D......",
+			".........",
+			"..A......",
+			"..D.K....",
+			".........",
+			".........",
+			"........A",
+			".........",
+		]);
+		let mut g = Tablut::from_board(&b, true);
+		for m in cycle.iter() {
+			g.mov(m);
+		}
+		assert_eq!(g.a.count_ones(), 1);
+		assert_eq!((g.state(), g.repetitions()), (State::Going, 1));
+		g.mov(&cycle[0]);
+		assert_eq!((g.state(), g.repetitions()), (State::Draw, 2));
+	}
+
 	#[test]
 	fn test_new_tablut() {
+		// old_tablut never ends in a draw
+		static NO_DRAW: TablutRules = TablutRules {
+			repetitions: 0,
+			..ASHTON
+		};
 		let mut rng = Xoroshiro128Plus::from_rng(rand::thread_rng()).unwrap();
 		for _ in 0..10000 {
 			let mut g = old_tablut::Tablut::new(true);
-			let mut g_new = Tablut::new(true);
+			let mut g_new = Tablut::with_rules(&NO_DRAW, true);
 			let mut rb = Vec::<<old_tablut::Tablut as Game>::R>::new();
 			let mut rb_new = Vec::<<Tablut as Game>::R>::new();
 			while g.state() == State::Going && g_new.state() == State::Going {
@@ -310,8 +2190,7 @@ mod tests {
 				eprintln!("g_new:\n{}", g_new);
 
 				eprintln!("rolling back");
-				while !rb.is_empty() {
-					let trb = rb.pop().unwrap();
+				while let Some(trb) = rb.pop() {
 					let trb_new = rb_new.pop().unwrap();
 					g.rollback(trb);
 					g_new.rollback(trb_new);
@@ -324,8 +2203,32 @@ mod tests {
 		}
 	}
 	use test::Bencher;
+	// positions of a few random games
+	fn positions<G: Game>() -> Vec<G> {
+		let mut rng = Xoroshiro128Plus::seed_from_u64(0);
+		let mut ans = vec![];
+		for _ in 0..8 {
+			let mut g = G::new(true);
+			while g.state() == State::Going {
+				ans.push(g.clone());
+				let m = *g.get_moves().choose(&mut rng).unwrap();
+				g.mov(&m);
+			}
+		}
+		ans
+	}
+	#[bench]
+	fn bench_othello_get_moves(b: &mut Bencher) {
+		let v = positions::<Othello>();
+		b.iter(|| v.iter().map(|g| g.get_moves().len()).sum::<usize>());
+	}
+	#[bench]
+	fn bench_old_othello_get_moves(b: &mut Bencher) {
+		let v = positions::<old_othello::Othello>();
+		b.iter(|| v.iter().map(|g| g.get_moves().len()).sum::<usize>());
+	}
 	#[bench]
 	fn bench_tablut(b: &mut Bencher) {
-		b.iter(|| super::random_play::<Tablut>());
+		b.iter(super::random_play::<Tablut>);
 	}
 }
@@ -1,21 +1,78 @@
 use crate::game::*;
 use std::cmp::Ordering;
 
+// what happens to the seeds still on the board when a side runs empty
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Sweep {
+	// each player gets the seeds on their side
+	Owner,
+	// they aren't counted
+	Nobody,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct MancalaRules {
+	// seeds in each pit at the start, 3 to 6
+	pub seeds: u8,
+	// the last seed landing in an empty pit of the mover takes the opposite pit
+	pub empty_capture: bool,
+	pub sweep: Sweep,
+}
+
+pub const KALAH: MancalaRules = MancalaRules {
+	seeds: 4,
+	empty_capture: true,
+	sweep: Sweep::Owner,
+};
+
+impl Default for MancalaRules {
+	fn default() -> Self {
+		KALAH
+	}
+}
+
+// drops the seeds of pit from one by one on the following pits, wrapping around
+// and leaving out the pits skip says, returns the pit of the last seed
+pub fn sow<F: Fn(usize) -> bool>(a: &mut [u8], from: usize, skip: F) -> usize {
+	let mut i = from;
+	let mut x = a[from];
+	a[from] = 0;
+	while x != 0 {
+		i = (i + 1) % a.len();
+		if skip(i) {
+			continue;
+		}
+		a[i] += 1;
+		x -= 1;
+	}
+	i
+}
+
 #[derive(Debug, Hash, Clone, Copy, PartialEq, Eq)]
 pub struct Mancala {
 	pub a: [u8; 14],
 	turn: bool,
+	pub rules: &'static MancalaRules,
+}
+
+impl Mancala {
+	pub fn with_rules(rules: &'static MancalaRules, t: bool) -> Self {
+		assert!((3..=6).contains(&rules.seeds));
+		let s = rules.seeds;
+		Mancala {
+			a: [s, s, s, s, s, s, 0, s, s, s, s, s, s, 0],
+			turn: t,
+			rules,
+		}
+	}
 }
 
 impl Game for Mancala {
 	type M = u8;
 	type S = ([u8; 14], bool);
-	type R = (u8, u8, bool);
+	type R = ([u8; 14], bool);
 	fn new(t: bool) -> Self {
-		Mancala {
-			a: [4, 4, 4, 4, 4, 4, 0, 4, 4, 4, 4, 4, 4, 0],
-			turn: t,
-		}
+		Self::with_rules(&KALAH, t)
 	}
 	fn turn(&self) -> bool {
 		self.turn
@@ -47,17 +104,21 @@ impl Game for Mancala {
 	fn state(&self) -> State {
 		let sumhd: u8 = self.a.iter().take(6).sum();
 		let sumhu: u8 = self.a.iter().skip(7).take(6).sum();
+		// more than half of the seeds can't be caught up with
+		let half = self.rules.seeds * 6;
 		if sumhd == 0 || sumhu == 0 {
-			let sumd = sumhd + self.a[6];
-			let sumu = sumhu + self.a[13];
+			let (sumd, sumu) = match self.rules.sweep {
+				Sweep::Owner => (sumhd + self.a[6], sumhu + self.a[13]),
+				Sweep::Nobody => (self.a[6], self.a[13]),
+			};
 			match sumd.cmp(&sumu) {
 				Ordering::Greater => State::Win,
 				Ordering::Less => State::Lose,
 				Ordering::Equal => State::Draw,
 			}
-		} else if self.a[6] > 24 {
+		} else if self.a[6] > half {
 			State::Win
-		} else if self.a[13] > 24 {
+		} else if self.a[13] > half {
 			State::Lose
 		} else {
 			State::Going
@@ -67,18 +128,14 @@ impl Game for Mancala {
 		(self.a, self.turn)
 	}
 	fn mov(&mut self, m: &u8) {
-		let mut i = *m as usize;
-		let mut x = self.a[i];
-		self.a[i] = 0;
-		while x != 0 {
-			i = (i + 1) % 14;
-			if (i == 13 && self.turn) || (i == 6 && !self.turn) {
-				continue;
-			}
-			self.a[i] += 1;
-			x -= 1;
-		}
-		if self.a[i] == 1 && ((self.turn && i < 6) || (!self.turn && i > 6 && i < 13)) {
+		let turn = self.turn;
+		let i = sow(&mut self.a, *m as usize, |i| {
+			(i == 13 && turn) || (i == 6 && !turn)
+		});
+		if self.rules.empty_capture
+			&& self.a[i] == 1
+			&& ((self.turn && i < 6) || (!self.turn && i > 6 && i < 13))
+		{
 			let o = 12 - i;
 			self.a[i] += self.a[o];
 			self.a[o] = 0;
@@ -88,55 +145,12 @@ impl Game for Mancala {
 		}
 	}
 	fn mov_with_rollback(&mut self, m: &u8) -> Self::R {
-		let mut rb: (u8, u8, bool) = (*m, self.a[*m as usize], false);
-		let mut i = *m as usize;
-		let mut x = self.a[i];
-		self.a[i] = 0;
-		while x != 0 {
-			i = (i + 1) % 14;
-			if (i == 13 && self.turn) || (i == 6 && !self.turn) {
-				continue;
-			}
-			self.a[i] += 1;
-			x -= 1;
-		}
-		if self.a[i] == 1 && ((self.turn && i < 6) || (!self.turn && i > 6 && i < 13)) {
-			let o = 12 - i;
-			rb.2 = true;
-			self.a[i] += self.a[o];
-			self.a[o] = 0;
-		}
-		if !((i == 6 && self.turn) || (i == 13 && !self.turn)) {
-			self.turn = !self.turn;
-		}
+		let rb = (self.a, self.turn);
+		self.mov(m);
 		rb
 	}
 	fn rollback(&mut self, rb: Self::R) {
-		let mut i = rb.0 as usize;
-		let mut x = rb.1;
-		self.turn = i < 6;
-		while x != 0 {
-			i = (i + 1) % 14;
-			if (i == 13 && self.turn) || (i == 6 && !self.turn) {
-				continue;
-			}
-			x -= 1;
-		}
-		if rb.2 {
-			self.a[12 - i] += self.a[i] - 1;
-			self.a[i] = 1;
-		}
-		x = rb.1;
-		while x != 0 {
-			if (i == 13 && self.turn) || (i == 6 && !self.turn) {
-				i = (i + 13) % 14;
-				continue;
-			}
-			self.a[i] -= 1;
-			i = (i + 13) % 14;
-			x -= 1;
-		}
-		self.a[i] = rb.1;
+		(self.a, self.turn) = rb;
 	}
 }
 /*
@@ -1,8 +1,11 @@
-use crate::ai::Ai;
+use crate::ai::*;
 use crate::game::*;
 use crate::heuristic::Heuristic;
 use std::marker::PhantomData;
 use std::mem::take;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
 use std::time::Duration;
 use std::time::Instant;
 
@@ -34,6 +37,8 @@ pub struct MinimaxFinal<G: Game, H: Heuristic<G>> {
 	st: Instant,
 	tl: Duration,
 	ended_early: bool,
+	nodes: u64,
+	stop: Arc<AtomicBool>,
 	_ph: PhantomData<H>,
 }
 
@@ -52,8 +57,9 @@ impl<G: Game, H: Heuristic<G>> MinimaxFinal<G, H> {
 			t.depth = depth;
 			return;
 		}
+		self.nodes += 1;
 		self.nnw = self.nnw.wrapping_add(1);
-		if self.nnw == 0 && self.st.elapsed() > self.tl {
+		if self.nnw == 0 && (self.st.elapsed() > self.tl || self.stop.load(Ordering::Relaxed)) {
 			self.ended_early = true;
 			return;
 		}
@@ -116,6 +122,8 @@ impl<G: Game, H: Heuristic<G>> Ai<G> for MinimaxFinal<G, H> {
 			st: Instant::now(),
 			tl: Duration::ZERO,
 			ended_early: false,
+			nodes: 0,
+			stop: Arc::new(AtomicBool::new(false)),
 			_ph: PhantomData,
 		}
 	}
@@ -129,9 +137,30 @@ impl<G: Game, H: Heuristic<G>> Ai<G> for MinimaxFinal<G, H> {
 		self.g.turn()
 	}
 	fn get_mov(&mut self, tl: Duration) -> G::M {
+		let tl = match solve_or_time_left::<G, H>(&self.g, tl) {
+			Ok((m, v)) => {
+				// the solved move as the only explored child, for info
+				self.tree = Tree {
+					val: v,
+					depth: 1,
+					children: vec![(
+						m,
+						Tree {
+							depth: 1,
+							..Tree::new()
+						},
+					)],
+				};
+				self.cur_depth = 1;
+				self.nodes = 0;
+				return m;
+			}
+			Err(tl) => tl,
+		};
 		self.st = Instant::now();
-		self.tl = tl - Duration::from_millis(20);
+		self.tl = tl.saturating_sub(Duration::from_millis(20));
 		self.ended_early = false;
+		self.nodes = 0;
 		let mut t = take(&mut self.tree);
 		while t.val > -30000 && t.val < 30000 && !self.ended_early {
 			self.cur_depth += 1;
@@ -167,4 +196,35 @@ impl<G: Game, H: Heuristic<G>> Ai<G> for MinimaxFinal<G, H> {
 		}
 		self.g.mov(m);
 	}
+	fn info(&self) -> Option<SearchInfo<G::M>> {
+		// follow the best explored child down the tree
+		let mut pv = vec![];
+		let mut g = self.g.clone();
+		let mut t = &self.tree;
+		while let Some(c) = if g.turn() {
+			t.children
+				.iter()
+				.min_by_key(|x| (u32::MAX - x.1.depth, -x.1.val))
+		} else {
+			t.children
+				.iter()
+				.min_by_key(|x| (u32::MAX - x.1.depth, x.1.val))
+		} {
+			if c.1.depth == 0 {
+				break;
+			}
+			pv.push(c.0);
+			g.mov(&c.0);
+			t = &c.1;
+		}
+		Some(SearchInfo {
+			depth: self.cur_depth - 1,
+			score: self.tree.val,
+			nodes: self.nodes,
+			pv,
+		})
+	}
+	fn set_stop(&mut self, stop: Arc<AtomicBool>) {
+		self.stop = stop;
+	}
 }
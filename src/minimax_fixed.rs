@@ -1,4 +1,4 @@
-use crate::ai::Ai;
+use crate::ai::*;
 use crate::game::*;
 use crate::heuristic::Heuristic;
 use std::marker::PhantomData;
@@ -6,6 +6,9 @@ use std::time::Duration;
 
 pub struct MinimaxFixed<G: Game, H: Heuristic<G>, const D: u32> {
 	pub g: G,
+	val: i64,
+	nodes: u64,
+	last_ans: G::M,
 	_ph: PhantomData<H>,
 }
 
@@ -14,6 +17,7 @@ impl<G: Game, H: Heuristic<G>, const D: u32> MinimaxFixed<G, H, D> {
 		if self.g.state() != State::Going || depth == 0 {
 			return H::eval(&self.g);
 		}
+		self.nodes += 1;
 		let moves = self.g.get_moves_sorted();
 		for m in moves.iter() {
 			let rb = self.g.mov_with_rollback(m);
@@ -58,6 +62,7 @@ impl<G: Game, H: Heuristic<G>, const D: u32> MinimaxFixed<G, H, D> {
 				break;
 			}
 		}
+		self.val = if self.g.turn() { a } else { b };
 		ans
 	}
 }
@@ -66,6 +71,9 @@ impl<G: Game, H: Heuristic<G>, const D: u32> Ai<G> for MinimaxFixed<G, H, D> {
 	fn new(t: bool) -> Self {
 		Self {
 			g: G::new(t),
+			val: 0,
+			nodes: 0,
+			last_ans: G::M::default(),
 			_ph: PhantomData,
 		}
 	}
@@ -80,9 +88,19 @@ impl<G: Game, H: Heuristic<G>, const D: u32> Ai<G> for MinimaxFixed<G, H, D> {
 	}
 	fn get_mov(&mut self, _tl: Duration) -> G::M {
 		eprintln!("minimax_fixed depth {}", D);
-		self.minimax_move(D)
+		self.nodes = 0;
+		self.last_ans = self.minimax_move(D);
+		self.last_ans
 	}
 	fn mov(&mut self, m: &G::M) {
 		self.g.mov(m);
 	}
+	fn info(&self) -> Option<SearchInfo<G::M>> {
+		Some(SearchInfo {
+			depth: D,
+			score: self.val,
+			nodes: self.nodes,
+			pv: vec![self.last_ans],
+		})
+	}
 }
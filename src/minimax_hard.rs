@@ -1,14 +1,21 @@
-use crate::ai::Ai;
+use crate::ai::*;
 use crate::game::*;
 use crate::heuristic::Heuristic;
 use std::collections::HashMap;
 use std::marker::PhantomData;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
 use std::time::Duration;
 use std::time::Instant;
 
 pub struct MinimaxHard<G: Game, H: Heuristic<G>> {
 	pub g: G,
 	table: HashMap<G::S, (i64, u32)>,
+	last_depth: u32,
+	last_ans: G::M,
+	nodes: u64,
+	stop: Arc<AtomicBool>,
 	_ph: PhantomData<H>,
 }
 
@@ -17,6 +24,8 @@ impl<G: Game, H: Heuristic<G>> MinimaxHard<G, H> {
 		if self.g.state() != State::Going || depth == 0 {
 			return H::eval(&self.g);
 		}
+		self.nodes += 1;
+		let window = (a, b);
 		let mut old_depth = 0;
 		if let Some(x) = self.table.get(&self.g.get_static_state()) {
 			if depth <= x.1 {
@@ -35,7 +44,7 @@ impl<G: Game, H: Heuristic<G>> MinimaxHard<G, H> {
 				.0;
 			self.g.rollback(rb);
 			if self.g.turn() {
-				-ans
+				ans.wrapping_neg()
 			} else {
 				ans
 			}
@@ -55,7 +64,8 @@ impl<G: Game, H: Heuristic<G>> MinimaxHard<G, H> {
 				break;
 			}
 		}
-		if depth > old_depth {
+		// on the window's bounds res is only a bound, the table keeps exact values
+		if depth > old_depth && window.0 < res && res < window.1 {
 			self.table.insert(self.g.get_static_state(), (res, depth));
 		}
 		res
@@ -83,7 +93,7 @@ impl<G: Game, H: Heuristic<G>> MinimaxHard<G, H> {
 				.0;
 			self.g.rollback(rb);
 			if self.g.turn() {
-				-ans
+				ans.wrapping_neg()
 			} else {
 				ans
 			}
@@ -123,6 +133,10 @@ impl<G: Game, H: Heuristic<G>> Ai<G> for MinimaxHard<G, H> {
 		Self {
 			g: G::new(t),
 			table: HashMap::new(),
+			last_depth: 0,
+			last_ans: G::M::default(),
+			nodes: 0,
+			stop: Arc::new(AtomicBool::new(false)),
 			_ph: PhantomData,
 		}
 	}
@@ -136,19 +150,45 @@ impl<G: Game, H: Heuristic<G>> Ai<G> for MinimaxHard<G, H> {
 		self.g.turn()
 	}
 	fn get_mov(&mut self, tl: Duration) -> G::M {
+		let tl = match solve_or_time_left::<G, H>(&self.g, tl) {
+			Ok((m, _)) => {
+				self.last_ans = m;
+				self.last_depth = 0;
+				self.nodes = 0;
+				return m;
+			}
+			Err(tl) => tl,
+		};
 		let start_time = Instant::now();
 		let mut depth = 1;
+		self.nodes = 0;
 		let mut ans = self.minimax_move(1);
 		loop {
-			if start_time.elapsed() * 20 > tl {
+			if start_time.elapsed() * 20 > tl || self.stop.load(Ordering::Relaxed) {
 				break;
 			}
 			depth += 1;
 			ans = self.minimax_move(depth);
 		}
+		self.last_depth = depth;
+		self.last_ans = ans;
 		ans
 	}
 	fn mov(&mut self, m: &G::M) {
 		self.g.mov(m);
 	}
+	fn info(&self) -> Option<SearchInfo<G::M>> {
+		Some(SearchInfo {
+			depth: self.last_depth,
+			score: self
+				.table
+				.get(&self.g.get_static_state())
+				.map_or(0, |x| x.0),
+			nodes: self.nodes,
+			pv: vec![self.last_ans],
+		})
+	}
+	fn set_stop(&mut self, stop: Arc<AtomicBool>) {
+		self.stop = stop;
+	}
 }
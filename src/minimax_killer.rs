@@ -1,8 +1,11 @@
-use crate::ai::Ai;
+use crate::ai::*;
 use crate::game::*;
 use crate::heuristic::Heuristic;
 use std::collections::VecDeque;
 use std::marker::PhantomData;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
 use std::time::Duration;
 use std::time::Instant;
 
@@ -14,6 +17,9 @@ pub struct MinimaxKiller<G: Game, H: Heuristic<G>> {
 	best_mov: VecDeque<G::M>,
 	ended_early: bool,
 	cur_depth: u32,
+	val: i64,
+	nodes: u64,
+	stop: Arc<AtomicBool>,
 	_ph: PhantomData<H>,
 }
 
@@ -22,8 +28,11 @@ impl<G: Game, H: Heuristic<G>> MinimaxKiller<G, H> {
 		if self.g.state() != State::Going || depth == 0 {
 			return H::eval(&self.g);
 		}
+		self.nodes += 1;
 		self.nnw = self.nnw.wrapping_add(1);
-		if self.ended_early || (self.nnw == 0 && self.st.elapsed() > self.tl) {
+		if self.ended_early
+			|| (self.nnw == 0 && (self.st.elapsed() > self.tl || self.stop.load(Ordering::Relaxed)))
+		{
 			self.ended_early = true;
 			return if self.g.turn() { a } else { b };
 		}
@@ -86,6 +95,9 @@ impl<G: Game, H: Heuristic<G>> Ai<G> for MinimaxKiller<G, H> {
 			best_mov: vd,
 			ended_early: false,
 			cur_depth: 0,
+			val: 0,
+			nodes: 0,
+			stop: Arc::new(AtomicBool::new(false)),
 			_ph: PhantomData,
 		}
 	}
@@ -99,13 +111,28 @@ impl<G: Game, H: Heuristic<G>> Ai<G> for MinimaxKiller<G, H> {
 		self.g.turn()
 	}
 	fn get_mov(&mut self, tl: Duration) -> G::M {
-		self.tl = tl - Duration::from_millis(20);
+		let tl = match solve_or_time_left::<G, H>(&self.g, tl) {
+			Ok((m, v)) => {
+				self.val = v;
+				self.cur_depth = 0;
+				self.nodes = 0;
+				self.best_mov.clear();
+				self.best_mov.push_back(m);
+				return m;
+			}
+			Err(tl) => tl,
+		};
+		self.tl = tl.saturating_sub(Duration::from_millis(20));
 		self.st = Instant::now();
 		self.ended_early = false;
+		self.nodes = 0;
 		while !self.ended_early {
 			self.cur_depth += 1;
 			self.best_mov.push_front(self.best_mov[0]);
-			self.minimax(i64::MIN, i64::MAX, self.cur_depth);
+			let h = self.minimax(i64::MIN, i64::MAX, self.cur_depth);
+			if !self.ended_early {
+				self.val = h;
+			}
 		}
 		self.cur_depth -= 1;
 		self.best_mov.pop_front();
@@ -119,4 +146,15 @@ impl<G: Game, H: Heuristic<G>> Ai<G> for MinimaxKiller<G, H> {
 			self.best_mov.pop_back();
 		}
 	}
+	fn info(&self) -> Option<SearchInfo<G::M>> {
+		Some(SearchInfo {
+			depth: self.cur_depth,
+			score: self.val,
+			nodes: self.nodes,
+			pv: vec![*self.best_mov.back().unwrap()],
+		})
+	}
+	fn set_stop(&mut self, stop: Arc<AtomicBool>) {
+		self.stop = stop;
+	}
 }
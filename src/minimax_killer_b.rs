@@ -1,8 +1,11 @@
-use crate::ai::Ai;
+use crate::ai::*;
 use crate::game::*;
 use crate::heuristic::Heuristic;
 use std::collections::VecDeque;
 use std::marker::PhantomData;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
 use std::time::Duration;
 use std::time::Instant;
 
@@ -15,6 +18,9 @@ pub struct MinimaxKillerB<G: Game, H: Heuristic<G>> {
 	global_best: VecDeque<G::M>,
 	ended_early: bool,
 	cur_depth: u32,
+	val: i64,
+	nodes: u64,
+	stop: Arc<AtomicBool>,
 	_ph: PhantomData<H>,
 }
 
@@ -27,8 +33,11 @@ impl<G: Game, H: Heuristic<G>> MinimaxKillerB<G, H> {
 		if self.g.state() != State::Going || depth == 0 {
 			return (H::eval(&self.g), mv);
 		}
+		self.nodes += 1;
 		self.nnw = self.nnw.wrapping_add(1);
-		if self.ended_early || (self.nnw == 0 && self.st.elapsed() > self.tl) {
+		if self.ended_early
+			|| (self.nnw == 0 && (self.st.elapsed() > self.tl || self.stop.load(Ordering::Relaxed)))
+		{
 			self.ended_early = true;
 			return (if self.g.turn() { a } else { b }, mv);
 		}
@@ -111,6 +120,9 @@ impl<G: Game, H: Heuristic<G>> Ai<G> for MinimaxKillerB<G, H> {
 			global_best: vd,
 			ended_early: false,
 			cur_depth: 0,
+			val: 0,
+			nodes: 0,
+			stop: Arc::new(AtomicBool::new(false)),
 			_ph: PhantomData,
 		}
 	}
@@ -124,9 +136,23 @@ impl<G: Game, H: Heuristic<G>> Ai<G> for MinimaxKillerB<G, H> {
 		self.g.turn()
 	}
 	fn get_mov(&mut self, tl: Duration) -> G::M {
-		self.tl = tl - Duration::from_millis(20);
+		let tl = match solve_or_time_left::<G, H>(&self.g, tl) {
+			Ok((m, v)) => {
+				self.val = v;
+				self.cur_depth = 0;
+				self.nodes = 0;
+				self.best_mov.clear();
+				self.best_mov.push_back(m);
+				self.global_best.clear();
+				self.global_best.push_back(m);
+				return m;
+			}
+			Err(tl) => tl,
+		};
+		self.tl = tl.saturating_sub(Duration::from_millis(20));
 		self.st = Instant::now();
 		self.ended_early = false;
+		self.nodes = 0;
 		let mut val = 0;
 		while !self.ended_early {
 			self.cur_depth += 1;
@@ -141,6 +167,7 @@ impl<G: Game, H: Heuristic<G>> Ai<G> for MinimaxKillerB<G, H> {
 		self.cur_depth -= 1;
 		self.best_mov.pop_front();
 		self.global_best.pop_front();
+		self.val = val;
 		eprintln!("minimax_killer_b depth {} val {}", self.cur_depth, val);
 		*self.best_mov.back().unwrap()
 	}
@@ -152,4 +179,21 @@ impl<G: Game, H: Heuristic<G>> Ai<G> for MinimaxKillerB<G, H> {
 			self.global_best.pop_back();
 		}
 	}
+	fn info(&self) -> Option<SearchInfo<G::M>> {
+		Some(SearchInfo {
+			depth: self.cur_depth,
+			score: self.val,
+			nodes: self.nodes,
+			pv: self
+				.global_best
+				.iter()
+				.rev()
+				.take(self.cur_depth.max(1) as usize)
+				.copied()
+				.collect(),
+		})
+	}
+	fn set_stop(&mut self, stop: Arc<AtomicBool>) {
+		self.stop = stop;
+	}
 }
@@ -1,7 +1,10 @@
-use crate::ai::Ai;
+use crate::ai::*;
 use crate::game::*;
 use crate::heuristic::Heuristic;
 use std::marker::PhantomData;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
 use std::time::Duration;
 use std::time::Instant;
 
@@ -12,6 +15,10 @@ pub struct MinimaxSimple<G: Game, H: Heuristic<G>> {
 	st: Instant,
 	last_ans: G::M,
 	ended_early: bool,
+	last_val: i64,
+	last_depth: u32,
+	nodes: u64,
+	stop: Arc<AtomicBool>,
 	_ph: PhantomData<H>,
 }
 
@@ -20,8 +27,11 @@ impl<G: Game, H: Heuristic<G>> MinimaxSimple<G, H> {
 		if self.g.state() != State::Going || depth == 0 {
 			return H::eval(&self.g);
 		}
+		self.nodes += 1;
 		self.nnw = self.nnw.wrapping_add(1);
-		if self.ended_early || (self.nnw == 0 && self.st.elapsed() > self.tl) {
+		if self.ended_early
+			|| (self.nnw == 0 && (self.st.elapsed() > self.tl || self.stop.load(Ordering::Relaxed)))
+		{
 			self.ended_early = true;
 			return if self.g.turn() { a } else { b };
 		}
@@ -46,6 +56,12 @@ impl<G: Game, H: Heuristic<G>> MinimaxSimple<G, H> {
 		}
 	}
 	fn minimax_move(&mut self, depth: u32) -> bool {
+		// the root looks at the clock too, with only game ending moves left
+		// no other node would
+		if depth > 1 && (self.st.elapsed() > self.tl || self.stop.load(Ordering::Relaxed)) {
+			self.ended_early = true;
+			return true;
+		}
 		let mut a = i64::MIN;
 		let mut b = i64::MAX;
 		let moves = self.g.get_moves_sorted();
@@ -73,6 +89,8 @@ impl<G: Game, H: Heuristic<G>> MinimaxSimple<G, H> {
 			true
 		} else {
 			self.last_ans = ans;
+			self.last_val = if self.g.turn() { a } else { b };
+			self.last_depth = depth;
 			false
 		}
 	}
@@ -87,6 +105,10 @@ impl<G: Game, H: Heuristic<G>> Ai<G> for MinimaxSimple<G, H> {
 			st: Instant::now(),
 			last_ans: G::M::default(),
 			ended_early: false,
+			last_val: 0,
+			last_depth: 0,
+			nodes: 0,
+			stop: Arc::new(AtomicBool::new(false)),
 			_ph: PhantomData,
 		}
 	}
@@ -100,10 +122,21 @@ impl<G: Game, H: Heuristic<G>> Ai<G> for MinimaxSimple<G, H> {
 		self.g.turn()
 	}
 	fn get_mov(&mut self, tl: Duration) -> G::M {
+		let tl = match solve_or_time_left::<G, H>(&self.g, tl) {
+			Ok((m, v)) => {
+				self.last_ans = m;
+				self.last_val = v;
+				self.last_depth = 0;
+				self.nodes = 0;
+				return m;
+			}
+			Err(tl) => tl,
+		};
 		let mut depth = 1;
-		self.tl = tl - Duration::from_millis(20);
+		self.tl = tl.saturating_sub(Duration::from_millis(20));
 		self.st = Instant::now();
 		self.ended_early = false;
+		self.nodes = 0;
 		while !self.minimax_move(depth) {
 			depth += 1;
 		}
@@ -113,4 +146,15 @@ impl<G: Game, H: Heuristic<G>> Ai<G> for MinimaxSimple<G, H> {
 	fn mov(&mut self, m: &G::M) {
 		self.g.mov(m);
 	}
+	fn info(&self) -> Option<SearchInfo<G::M>> {
+		Some(SearchInfo {
+			depth: self.last_depth,
+			score: self.last_val,
+			nodes: self.nodes,
+			pv: vec![self.last_ans],
+		})
+	}
+	fn set_stop(&mut self, stop: Arc<AtomicBool>) {
+		self.stop = stop;
+	}
 }
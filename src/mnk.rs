@@ -0,0 +1,340 @@
+// m,n,k-games: W x H board, the first to get K stones in a line wins.
+// Tictactoe is 3,3,3 and Gomoku 15,15,5, optionally with the swap2 opening
+// and the renju restrictions on black.
+// Stones are black and white, black moves first and belongs to the first player
+// unless the swap2 opening gives it to the other one.
+use crate::game::*;
+
+pub const EMPTY: u8 = 0;
+pub const BLACK: u8 = 1;
+pub const WHITE: u8 = 2;
+// the player to move takes black and the other one plays white next, only in swap2
+pub const SWAP: u16 = u16::MAX;
+// on a square: swap2's third option, white there and then black somewhere else
+pub const MORE: u16 = 0x8000;
+const DIRS: [(i32, i32); 4] = [(1, 0), (0, 1), (1, 1), (1, -1)];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct MnkRules {
+	// only exactly K in a line wins, longer lines don't
+	pub exact: bool,
+	// the first player places black, white, black, the second one chooses black (SWAP),
+	// white playing a stone, or to place white and black (MORE) and let the first one choose
+	pub swap2: bool,
+	// black can't play overlines, double fours and double threes unless it makes K,
+	// white's overlines win
+	pub renju: bool,
+}
+
+pub const FREESTYLE: MnkRules = MnkRules {
+	exact: false,
+	swap2: false,
+	renju: false,
+};
+
+pub const STANDARD: MnkRules = MnkRules {
+	exact: true,
+	swap2: true,
+	renju: false,
+};
+
+pub const RENJU: MnkRules = MnkRules {
+	exact: false,
+	swap2: false,
+	renju: true,
+};
+
+impl Default for MnkRules {
+	fn default() -> Self {
+		FREESTYLE
+	}
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Phase {
+	#[default]
+	Play,
+	// swap2: the first three stones
+	Open,
+	// swap2: the second player chooses
+	Choose,
+	// swap2: the second player places black after MORE
+	More,
+	// swap2: the first player chooses
+	Choose2,
+}
+
+#[derive(Debug, Hash, Clone, Copy, PartialEq, Eq)]
+pub struct Mnk<const W: usize, const H: usize, const K: usize> {
+	pub a: [[u8; W]; H],
+	turn: bool,
+	// the player with the black stones
+	black: bool,
+	phase: Phase,
+	stones: usize,
+	state: State,
+	pub rules: &'static MnkRules,
+}
+
+pub type Tictactoe = Mnk<3, 3, 3>;
+pub type Gomoku = Mnk<15, 15, 5>;
+
+impl<const W: usize, const H: usize, const K: usize> Mnk<W, H, K> {
+	pub fn with_rules(rules: &'static MnkRules, t: bool) -> Self {
+		assert!(W * H < MORE as usize && K <= W.max(H));
+		Mnk {
+			a: [[EMPTY; W]; H],
+			turn: t,
+			black: t,
+			phase: if rules.swap2 {
+				Phase::Open
+			} else {
+				Phase::Play
+			},
+			stones: 0,
+			state: State::Going,
+			rules,
+		}
+	}
+	pub fn black(&self) -> bool {
+		self.black
+	}
+	pub fn phase(&self) -> Phase {
+		self.phase
+	}
+	pub fn get(&self, x: i32, y: i32) -> Option<u8> {
+		if x < 0 || y < 0 || x >= W as i32 || y >= H as i32 {
+			None
+		} else {
+			Some(self.a[y as usize][x as usize])
+		}
+	}
+	// stones of color c in a row from (x, y) excluded in the direction
+	pub fn run(&self, x: i32, y: i32, (dx, dy): (i32, i32), c: u8) -> usize {
+		let mut n = 0;
+		while self.get(x + dx * (n + 1), y + dy * (n + 1)) == Some(c) {
+			n += 1;
+		}
+		n as usize
+	}
+	// longest line through (x, y) for color c, in the direction
+	fn line(&self, x: i32, y: i32, d: (i32, i32), c: u8) -> usize {
+		1 + self.run(x, y, d, c) + self.run(x, y, (-d.0, -d.1), c)
+	}
+	fn wins(&self, len: usize, c: u8) -> bool {
+		if self.rules.exact || (self.rules.renju && c == BLACK) {
+			len == K
+		} else {
+			len >= K
+		}
+	}
+	// color of the next stone
+	fn color(&self) -> u8 {
+		match self.phase {
+			Phase::Open if self.stones % 2 == 1 => WHITE,
+			Phase::Open | Phase::More => BLACK,
+			Phase::Choose | Phase::Choose2 => WHITE,
+			Phase::Play => {
+				if self.turn == self.black {
+					BLACK
+				} else {
+					WHITE
+				}
+			}
+		}
+	}
+	// with black at (x, y): the number of directions with a four, one stone from K,
+	// and with an open three, one stone from a four open on both ends
+	fn fours_threes(&mut self, x: i32, y: i32) -> (usize, usize) {
+		let (mut fours, mut threes) = (0, 0);
+		for d in DIRS {
+			let mut four = false;
+			let mut three = false;
+			for i in -(K as i32 - 1)..K as i32 {
+				let (qx, qy) = (x + d.0 * i, y + d.1 * i);
+				if i == 0 || self.get(qx, qy) != Some(EMPTY) {
+					continue;
+				}
+				self.a[qy as usize][qx as usize] = BLACK;
+				// the line through q reaches back - 1 before it and fwd - 1 after it
+				let back = self.run(qx, qy, (-d.0, -d.1), BLACK) as i32 + 1;
+				let fwd = self.run(qx, qy, d, BLACK) as i32 + 1;
+				if -fwd < i && i < back {
+					if back + fwd - 1 == K as i32 {
+						four = true;
+					} else if back + fwd - 1 == K as i32 - 1 {
+						// a straight four: both ends free and making exactly K
+						let (ex, ey) = (qx - d.0 * back, qy - d.1 * back);
+						let (fx, fy) = (qx + d.0 * fwd, qy + d.1 * fwd);
+						three |= self.get(ex, ey) == Some(EMPTY)
+							&& self.get(fx, fy) == Some(EMPTY)
+							&& self.get(ex - d.0, ey - d.1) != Some(BLACK)
+							&& self.get(fx + d.0, fy + d.1) != Some(BLACK);
+					}
+				}
+				self.a[qy as usize][qx as usize] = EMPTY;
+			}
+			fours += four as usize;
+			threes += (three && !four) as usize;
+		}
+		(fours, threes)
+	}
+	// renju: black can't play (x, y)
+	pub fn forbidden(&self, x: i32, y: i32) -> bool {
+		let mut g = *self;
+		g.a[y as usize][x as usize] = BLACK;
+		let lines = DIRS.map(|d| g.line(x, y, d, BLACK));
+		if lines.contains(&K) {
+			return false;
+		}
+		if lines.iter().any(|l| *l > K) {
+			return true;
+		}
+		let (fours, threes) = g.fours_threes(x, y);
+		fours >= 2 || threes >= 2
+	}
+	fn squares(&self) -> Vec<u16> {
+		let renju = self.rules.renju && self.phase == Phase::Play && self.color() == BLACK;
+		let mut ans = Vec::with_capacity(W * H - self.stones);
+		for y in 0..H {
+			for x in 0..W {
+				if self.a[y][x] == EMPTY && !(renju && self.forbidden(x as i32, y as i32)) {
+					ans.push((y * W + x) as u16);
+				}
+			}
+		}
+		ans
+	}
+	// the choices on top of the squares
+	fn with_choices(&self, squares: Vec<u16>) -> Vec<u16> {
+		match self.phase {
+			Phase::Choose => std::iter::once(SWAP)
+				.chain(squares.iter().copied())
+				.chain(squares.iter().map(|m| m | MORE))
+				.collect(),
+			Phase::Choose2 => std::iter::once(SWAP).chain(squares).collect(),
+			_ => squares,
+		}
+	}
+}
+
+impl<const W: usize, const H: usize, const K: usize> Game for Mnk<W, H, K> {
+	type M = u16; // y * W + x, maybe | MORE, or SWAP
+	type S = ([[u8; W]; H], bool, bool, Phase);
+	type R = (u16, bool, bool, Phase);
+	fn new(t: bool) -> Self {
+		Self::with_rules(&FREESTYLE, t)
+	}
+	fn turn(&self) -> bool {
+		self.turn
+	}
+	fn get_moves(&self) -> Vec<u16> {
+		self.with_choices(self.squares())
+	}
+	fn get_moves_sorted(&self) -> Vec<u16> {
+		// next to the stones first, then close to the center
+		let mut squares = self.squares();
+		squares.sort_by_cached_key(|m| {
+			let (x, y) = ((*m as usize % W) as i32, (*m as usize / W) as i32);
+			let mut near = 0;
+			for dy in -2..=2 {
+				for dx in -2..=2 {
+					if self.get(x + dx, y + dy).is_some_and(|c| c != EMPTY) {
+						near += if dx.abs() <= 1 && dy.abs() <= 1 { 2 } else { 1 };
+					}
+				}
+			}
+			let center = (2 * x - W as i32 + 1).abs() + (2 * y - H as i32 + 1).abs();
+			(-near, center)
+		});
+		self.with_choices(squares)
+	}
+	fn get_static_state(&self) -> Self::S {
+		(self.a, self.turn, self.black, self.phase)
+	}
+	fn state(&self) -> State {
+		self.state
+	}
+	fn mov(&mut self, m: &u16) {
+		if *m == SWAP {
+			self.black = self.turn;
+			self.phase = Phase::Play;
+			self.turn = !self.turn;
+			return;
+		}
+		let p = (*m & !MORE) as usize;
+		let (x, y) = (p % W, p / W);
+		let c = self.color();
+		self.a[y][x] = c;
+		self.stones += 1;
+		(self.phase, self.turn) = match self.phase {
+			Phase::Open if self.stones < 3 => (Phase::Open, self.turn),
+			Phase::Open | Phase::More => (
+				if self.phase == Phase::Open {
+					Phase::Choose
+				} else {
+					Phase::Choose2
+				},
+				!self.turn,
+			),
+			Phase::Choose if *m & MORE != 0 => {
+				self.black = !self.turn;
+				(Phase::More, self.turn)
+			}
+			Phase::Choose | Phase::Choose2 => {
+				self.black = !self.turn;
+				(Phase::Play, !self.turn)
+			}
+			Phase::Play => (Phase::Play, !self.turn),
+		};
+		self.state = if DIRS
+			.iter()
+			.any(|d| self.wins(self.line(x as i32, y as i32, *d, c), c))
+		{
+			// the player with the color
+			if (c == BLACK) == self.black {
+				State::Win
+			} else {
+				State::Lose
+			}
+		} else if self.stones == W * H {
+			State::Draw
+		} else {
+			State::Going
+		};
+	}
+	fn mov_with_rollback(&mut self, m: &u16) -> Self::R {
+		let rb = (*m, self.turn, self.black, self.phase);
+		self.mov(m);
+		rb
+	}
+	fn rollback(&mut self, (m, turn, black, phase): Self::R) {
+		if m != SWAP {
+			let p = (m & !MORE) as usize;
+			self.a[p / W][p % W] = EMPTY;
+			self.stones -= 1;
+		}
+		(self.turn, self.black, self.phase) = (turn, black, phase);
+		self.state = State::Going;
+	}
+}
+
+impl<const W: usize, const H: usize, const K: usize> std::fmt::Display for Mnk<W, H, K> {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		for row in self.a.iter() {
+			for c in row.iter() {
+				write!(
+					f,
+					"{}",
+					match *c {
+						BLACK => 'X',
+						WHITE => 'O',
+						_ => '.',
+					}
+				)?;
+			}
+			writeln!(f)?;
+		}
+		Ok(())
+	}
+}
@@ -1,23 +1,28 @@
-use crate::ai::Ai;
+use crate::ai::*;
 use crate::game::*;
 use rand::prelude::SliceRandom;
 use rand::*;
 use rand_xoshiro::Xoroshiro128Plus;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
 use std::time::Duration;
 use std::time::Instant;
 
 pub struct MonteCarloTotal<G: Game> {
 	pub g: G,
 	rng: Xoroshiro128Plus,
+	last: (G::M, i64, u64),
+	stop: Arc<AtomicBool>,
 }
 
 impl<G: Game> MonteCarloTotal<G> {
 	fn explore_branch(&mut self, m0: &G::M, turn: bool) -> u32 {
-		self.g.mov(&m0);
+		self.g.mov(m0);
 		while self.g.state() == State::Going {
 			let moves = self.g.get_moves();
 			let m = moves.choose(&mut self.rng).unwrap();
-			self.g.mov(&m);
+			self.g.mov(m);
 		}
 		let mut ans = match self.g.state() {
 			State::Win => 1,
@@ -36,6 +41,8 @@ impl<G: Game> Ai<G> for MonteCarloTotal<G> {
 		Self {
 			g: G::new(t),
 			rng: Xoroshiro128Plus::from_rng(rand::thread_rng()).unwrap(),
+			last: (G::M::default(), 0, 0),
+			stop: Arc::new(AtomicBool::new(false)),
 		}
 	}
 	fn state(&self) -> State {
@@ -49,24 +56,30 @@ impl<G: Game> Ai<G> for MonteCarloTotal<G> {
 	}
 	fn get_mov(&mut self, mut tl: Duration) -> G::M {
 		let start_time = Instant::now();
-		tl -= Duration::from_millis(20);
+		tl = tl.saturating_sub(Duration::from_millis(20));
 		let moves = self.g.get_moves();
 		let turn = self.g.turn();
 		let mut v = vec![0u32; moves.len()];
 		let mut i = 0;
 		let g0 = self.g.clone();
 		loop {
-			if start_time.elapsed() > tl {
+			if start_time.elapsed() > tl || self.stop.load(Ordering::Relaxed) {
 				break;
 			}
 			i += 1;
 			for mm in moves.iter().enumerate() {
 				v[mm.0] += self.explore_branch(mm.1, turn);
-				self.g = g0.clone();
+				self.g.clone_from(&g0);
 			}
 		}
 		let best_ind = v.iter().enumerate().max_by_key(|x| x.1).unwrap().0;
 		let ans = moves[best_ind];
+		let permille = (1000 * v[best_ind] as u64 / (i as u64).max(1)) as i64;
+		self.last = (
+			ans,
+			if turn { permille } else { 1000 - permille },
+			i as u64 * moves.len() as u64,
+		);
 		eprintln!(
 			"monte_carlo_total chose move in {} milliseconds with {} iterations | confidence: {}",
 			start_time.elapsed().as_millis(),
@@ -78,4 +91,16 @@ impl<G: Game> Ai<G> for MonteCarloTotal<G> {
 	fn mov(&mut self, m: &G::M) {
 		self.g.mov(m);
 	}
+	// score is the permille of playouts after the best move won by player true
+	fn info(&self) -> Option<SearchInfo<G::M>> {
+		Some(SearchInfo {
+			depth: 1,
+			score: self.last.1,
+			nodes: self.last.2,
+			pv: vec![self.last.0],
+		})
+	}
+	fn set_stop(&mut self, stop: Arc<AtomicBool>) {
+		self.stop = stop;
+	}
 }
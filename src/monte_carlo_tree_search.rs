@@ -1,8 +1,11 @@
-use crate::ai::Ai;
+use crate::ai::*;
 use crate::game::*;
 use rand::prelude::SliceRandom;
 use rand::*;
 use rand_xoshiro::Xoroshiro128Plus;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
 use std::time::Duration;
 use std::time::Instant;
 
@@ -32,6 +35,8 @@ pub struct MonteCarloTreeSearch<G: Game> {
 	pub g: G,
 	rng: Xoroshiro128Plus,
 	tree: Tree<G>,
+	iterations: u64,
+	stop: Arc<AtomicBool>,
 }
 
 impl<G: Game> MonteCarloTreeSearch<G> {
@@ -46,7 +51,7 @@ impl<G: Game> MonteCarloTreeSearch<G> {
 		while self.g.state() == State::Going {
 			let moves = self.g.get_moves();
 			let m = moves.choose(&mut self.rng).unwrap();
-			self.g.mov(&m);
+			self.g.mov(m);
 		}
 		self.result_u32(self.g.state())
 	}
@@ -91,6 +96,8 @@ impl<G: Game> Ai<G> for MonteCarloTreeSearch<G> {
 			g: G::new(t),
 			rng: Xoroshiro128Plus::from_rng(rand::thread_rng()).unwrap(),
 			tree: Tree::<G>::new(),
+			iterations: 0,
+			stop: Arc::new(AtomicBool::new(false)),
 		}
 	}
 	fn state(&self) -> State {
@@ -104,7 +111,7 @@ impl<G: Game> Ai<G> for MonteCarloTreeSearch<G> {
 	}
 	fn get_mov(&mut self, mut tl: Duration) -> G::M {
 		let start_time = Instant::now();
-		tl -= Duration::from_millis(20);
+		tl = tl.saturating_sub(Duration::from_millis(20));
 		let moves = self.g.get_moves();
 		let mut i = 0;
 		let mut t = std::mem::take(&mut self.tree);
@@ -112,14 +119,15 @@ impl<G: Game> Ai<G> for MonteCarloTreeSearch<G> {
 		loop {
 			for _ in 0..32 {
 				self.step(&mut t);
-				self.g = g0.clone();
+				self.g.clone_from(&g0);
 			}
 			i += 32;
-			if start_time.elapsed() > tl {
+			if start_time.elapsed() > tl || self.stop.load(Ordering::Relaxed) {
 				break;
 			}
 		}
 		self.tree = std::mem::take(&mut t);
+		self.iterations = i;
 		let mut best_mov = moves[0];
 		let mut best_val = 0;
 		for (i, t) in self.tree.children.iter().enumerate() {
@@ -150,4 +158,25 @@ impl<G: Game> Ai<G> for MonteCarloTreeSearch<G> {
 			self.tree = std::mem::take(&mut t.children[movi])
 		}
 	}
+	// score is the permille of playouts won by player true
+	fn info(&self) -> Option<SearchInfo<G::M>> {
+		let mut pv = vec![];
+		let mut t = &self.tree;
+		while let Some((i, c)) = t.children.iter().enumerate().max_by_key(|x| x.1.vis) {
+			if c.vis == 0 {
+				break;
+			}
+			pv.push(t.movs[i]);
+			t = c;
+		}
+		Some(SearchInfo {
+			depth: pv.len() as u32,
+			score: (1000 * self.tree.wins as u64 / self.tree.vis.max(1) as u64) as i64,
+			nodes: self.iterations,
+			pv,
+		})
+	}
+	fn set_stop(&mut self, stop: Arc<AtomicBool>) {
+		self.stop = stop;
+	}
 }
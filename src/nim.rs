@@ -0,0 +1,179 @@
+// Small impartial games with a known theory, to check searches against: Nim on N heaps
+// and the subtraction game on one heap. A move takes from one heap, in the subtraction
+// game only one of the allowed amounts, the player who can't move loses.
+// The player to move wins exactly when the Sprague-Grundy value is not 0, the xor of
+// the heaps for Nim, see grundy for the subtraction game.
+use crate::game::*;
+
+pub const SUBTRACT: [u8; 3] = [1, 3, 4];
+
+#[derive(Debug, Hash, Clone, Copy, PartialEq, Eq)]
+pub struct Nim<const N: usize> {
+	pub heaps: [u8; N],
+	turn: bool,
+}
+
+impl<const N: usize> Nim<N> {
+	pub fn with_heaps(heaps: [u8; N], t: bool) -> Self {
+		Nim { heaps, turn: t }
+	}
+	pub fn nim_sum(&self) -> u8 {
+		self.heaps.iter().fold(0, |a, h| a ^ h)
+	}
+}
+
+// the player to move loses with nothing left, whose turn it is shows who that is
+fn no_moves(turn: bool) -> State {
+	if turn {
+		State::Lose
+	} else {
+		State::Win
+	}
+}
+
+impl<const N: usize> Game for Nim<N> {
+	type M = (u8, u8); // heap and how many
+	type S = ([u8; N], bool);
+	type R = (u8, u8);
+	// heaps of 3, 4, 5...
+	fn new(t: bool) -> Self {
+		let mut heaps = [0; N];
+		for (i, h) in heaps.iter_mut().enumerate() {
+			*h = i as u8 + 3;
+		}
+		Self::with_heaps(heaps, t)
+	}
+	fn turn(&self) -> bool {
+		self.turn
+	}
+	fn get_moves(&self) -> Vec<(u8, u8)> {
+		let mut ans = vec![];
+		for (i, h) in self.heaps.iter().enumerate() {
+			for n in 1..=*h {
+				ans.push((i as u8, n));
+			}
+		}
+		ans
+	}
+	fn get_moves_sorted(&self) -> Vec<(u8, u8)> {
+		self.get_moves()
+	}
+	fn get_static_state(&self) -> Self::S {
+		(self.heaps, self.turn)
+	}
+	fn state(&self) -> State {
+		if self.heaps.iter().all(|h| *h == 0) {
+			no_moves(self.turn)
+		} else {
+			State::Going
+		}
+	}
+	fn mov(&mut self, m: &(u8, u8)) {
+		self.heaps[m.0 as usize] -= m.1;
+		self.turn = !self.turn;
+	}
+	fn mov_with_rollback(&mut self, m: &(u8, u8)) -> Self::R {
+		self.mov(m);
+		*m
+	}
+	fn rollback(&mut self, m: Self::R) {
+		self.heaps[m.0 as usize] += m.1;
+		self.turn = !self.turn;
+	}
+}
+
+impl<const N: usize> std::fmt::Display for Nim<N> {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		for h in self.heaps.iter() {
+			writeln!(f, "{}", "|".repeat(*h as usize))?;
+		}
+		Ok(())
+	}
+}
+
+// Sprague-Grundy value of a heap of n with the allowed amounts: the smallest value
+// not reached in one move
+pub fn grundy(n: u8, takes: &[u8]) -> u8 {
+	let mut g = vec![0u8; n as usize + 1];
+	for i in 1..=n as usize {
+		let reached: Vec<u8> = takes
+			.iter()
+			.filter(|t| **t as usize <= i)
+			.map(|t| g[i - *t as usize])
+			.collect();
+		g[i] = (0..).find(|v| !reached.contains(v)).unwrap();
+	}
+	g[n as usize]
+}
+
+#[derive(Debug, Hash, Clone, Copy, PartialEq, Eq)]
+pub struct Subtraction {
+	pub heap: u8,
+	pub takes: &'static [u8],
+	turn: bool,
+}
+
+impl Subtraction {
+	pub fn with_takes(heap: u8, takes: &'static [u8], t: bool) -> Self {
+		Subtraction {
+			heap,
+			takes,
+			turn: t,
+		}
+	}
+	pub fn grundy(&self) -> u8 {
+		grundy(self.heap, self.takes)
+	}
+}
+
+impl Game for Subtraction {
+	type M = u8; // how many
+	type S = (u8, bool);
+	type R = u8;
+	// 20 taking 1, 3 or 4
+	fn new(t: bool) -> Self {
+		Self::with_takes(20, &SUBTRACT, t)
+	}
+	fn turn(&self) -> bool {
+		self.turn
+	}
+	fn get_moves(&self) -> Vec<u8> {
+		self
+			.takes
+			.iter()
+			.copied()
+			.filter(|n| *n <= self.heap)
+			.collect()
+	}
+	fn get_moves_sorted(&self) -> Vec<u8> {
+		self.get_moves()
+	}
+	fn get_static_state(&self) -> Self::S {
+		(self.heap, self.turn)
+	}
+	fn state(&self) -> State {
+		if self.takes.iter().all(|n| *n > self.heap) {
+			no_moves(self.turn)
+		} else {
+			State::Going
+		}
+	}
+	fn mov(&mut self, m: &u8) {
+		self.heap -= m;
+		self.turn = !self.turn;
+	}
+	fn mov_with_rollback(&mut self, m: &u8) -> Self::R {
+		self.mov(m);
+		*m
+	}
+	fn rollback(&mut self, m: Self::R) {
+		self.heap += m;
+		self.turn = !self.turn;
+	}
+}
+
+impl std::fmt::Display for Subtraction {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		writeln!(f, "{} taking {:?}", self.heap, self.takes)
+	}
+}
@@ -0,0 +1,387 @@
+use crate::amazons::Amazons;
+use crate::breakthrough::Breakthrough;
+use crate::checkers;
+use crate::checkers::Checkers;
+use crate::connect4::Connect4;
+use crate::dots_and_boxes::DotsAndBoxes;
+use crate::game::*;
+use crate::go::*;
+use crate::hex::Hex;
+use crate::mancala::Mancala;
+use crate::mnk::*;
+use crate::old_tictactoe;
+use crate::othello::Othello;
+use crate::oware::Oware;
+use crate::tablut::Tablut;
+use crate::tafl::*;
+use crate::ultimate_tictactoe::UltimateTictactoe;
+
+// human readable moves, used wherever moves cross a text boundary
+pub trait Notation: Game {
+	fn mov_to_string(&self, m: &Self::M) -> String;
+	// does not check legality, only syntax
+	fn parse_mov(&self, s: &str) -> Option<Self::M>;
+}
+
+// "a1" is x=0, y=0, letters are columns
+pub fn square_to_string(x: u8, y: u8) -> String {
+	format!("{}{}", (b'a' + x) as char, y + 1)
+}
+pub fn parse_square(s: &str) -> Option<(u8, u8)> {
+	let s = s.trim().to_ascii_lowercase();
+	let mut c = s.chars();
+	let col = c.next()?;
+	if !col.is_ascii_lowercase() {
+		return None;
+	}
+	let row: u8 = c.as_str().parse().ok()?;
+	if row == 0 {
+		return None;
+	}
+	Some((col as u8 - b'a', row - 1))
+}
+
+impl Notation for Tablut {
+	// "e3-e1", "pass" for the no moves left move
+	fn mov_to_string(&self, m: &Self::M) -> String {
+		if m.0 == m.1 {
+			return "pass".to_string();
+		}
+		format!(
+			"{}-{}",
+			square_to_string(m.0 % 11 - 1, m.0 / 11 - 1),
+			square_to_string(m.1 % 11 - 1, m.1 / 11 - 1)
+		)
+	}
+	fn parse_mov(&self, s: &str) -> Option<Self::M> {
+		let s = s.trim();
+		if s == "pass" {
+			return Some((0, 0));
+		}
+		let (from, to) = s.split_once('-')?;
+		let from = parse_square(from)?;
+		let to = parse_square(to)?;
+		if from.0 > 8 || from.1 > 8 || to.0 > 8 || to.1 > 8 {
+			return None;
+		}
+		Some((from.1 * 11 + from.0 + 12, to.1 * 11 + to.0 + 12))
+	}
+}
+
+impl<V: Variant> Notation for Tafl<V> {
+	// like Tablut, "d1-d3" and "pass"
+	fn mov_to_string(&self, m: &Self::M) -> String {
+		if m.0 == m.1 {
+			return "pass".to_string();
+		}
+		let from = self.xy(m.0 as usize);
+		let to = self.xy(m.1 as usize);
+		format!(
+			"{}-{}",
+			square_to_string(from.0 as u8 - 1, from.1 as u8 - 1),
+			square_to_string(to.0 as u8 - 1, to.1 as u8 - 1)
+		)
+	}
+	fn parse_mov(&self, s: &str) -> Option<Self::M> {
+		let s = s.trim();
+		if s == "pass" {
+			return Some((0, 0));
+		}
+		let (from, to) = s.split_once('-')?;
+		let from = parse_square(from)?;
+		let to = parse_square(to)?;
+		let n = self.rules.size;
+		if from.0 >= n || from.1 >= n || to.0 >= n || to.1 >= n {
+			return None;
+		}
+		let p = |x: (u8, u8)| ((x.1 as usize + 1) * self.stride() + x.0 as usize + 1) as u8;
+		Some((p(from), p(to)))
+	}
+}
+
+impl Notation for Othello {
+	fn mov_to_string(&self, m: &Self::M) -> String {
+		if *m == 64 {
+			"pass".to_string()
+		} else {
+			square_to_string(m & 7, m >> 3)
+		}
+	}
+	fn parse_mov(&self, s: &str) -> Option<Self::M> {
+		if s.trim() == "pass" {
+			return Some(64);
+		}
+		let (x, y) = parse_square(s)?;
+		if x > 7 || y > 7 {
+			return None;
+		}
+		Some((y << 3) | x)
+	}
+}
+
+impl Notation for Mancala {
+	// pits are numbered 1 to 6 from the point of view of the player to move
+	fn mov_to_string(&self, m: &Self::M) -> String {
+		format!("{}", if *m < 6 { m + 1 } else { m - 6 })
+	}
+	fn parse_mov(&self, s: &str) -> Option<Self::M> {
+		let i: u8 = s.trim().parse().ok()?;
+		if !(1..=6).contains(&i) {
+			return None;
+		}
+		Some(if self.turn() { i - 1 } else { i + 6 })
+	}
+}
+
+impl Notation for Oware {
+	// houses are numbered 1 to 6 from the point of view of the player to move
+	fn mov_to_string(&self, m: &Self::M) -> String {
+		format!("{}", m % 6 + 1)
+	}
+	fn parse_mov(&self, s: &str) -> Option<Self::M> {
+		let i: u8 = s.trim().parse().ok()?;
+		if !(1..=6).contains(&i) {
+			return None;
+		}
+		Some(if self.turn() { i - 1 } else { i + 5 })
+	}
+}
+
+impl Notation for UltimateTictactoe {
+	// squares of the whole 9x9 grid
+	fn mov_to_string(&self, m: &Self::M) -> String {
+		let (b, c) = (m / 9, m % 9);
+		square_to_string(b % 3 * 3 + c % 3, b / 3 * 3 + c / 3)
+	}
+	fn parse_mov(&self, s: &str) -> Option<Self::M> {
+		let (x, y) = parse_square(s)?;
+		if x > 8 || y > 8 {
+			return None;
+		}
+		Some((y / 3 * 3 + x / 3) * 9 + y % 3 * 3 + x % 3)
+	}
+}
+
+impl<const N: usize> Notation for Go<N> {
+	// "c3" or "pass"
+	fn mov_to_string(&self, m: &Self::M) -> String {
+		if *m == PASS {
+			return "pass".to_string();
+		}
+		square_to_string((*m as usize % N) as u8, (*m as usize / N) as u8)
+	}
+	fn parse_mov(&self, s: &str) -> Option<Self::M> {
+		if s.trim() == "pass" {
+			return Some(PASS);
+		}
+		let (x, y) = parse_square(s)?;
+		if x as usize >= N || y as usize >= N {
+			return None;
+		}
+		Some((y as usize * N + x as usize) as u16)
+	}
+}
+
+impl<const N: usize> Notation for Hex<N> {
+	// "c3" or "swap"
+	fn mov_to_string(&self, m: &Self::M) -> String {
+		if *m == crate::hex::SWAP {
+			return "swap".to_string();
+		}
+		square_to_string((*m as usize % N) as u8, (*m as usize / N) as u8)
+	}
+	fn parse_mov(&self, s: &str) -> Option<Self::M> {
+		if s.trim() == "swap" {
+			return Some(crate::hex::SWAP);
+		}
+		let (x, y) = parse_square(s)?;
+		if x as usize >= N || y as usize >= N {
+			return None;
+		}
+		Some((y as usize * N + x as usize) as u16)
+	}
+}
+
+impl Notation for Breakthrough {
+	// "a2-b3"
+	fn mov_to_string(&self, m: &Self::M) -> String {
+		format!(
+			"{}-{}",
+			square_to_string(m.0 % 8, m.0 / 8),
+			square_to_string(m.1 % 8, m.1 / 8)
+		)
+	}
+	fn parse_mov(&self, s: &str) -> Option<Self::M> {
+		let (from, to) = s.trim().split_once('-')?;
+		let from = parse_square(from)?;
+		let to = parse_square(to)?;
+		if from.0 > 7 || from.1 > 7 || to.0 > 7 || to.1 > 7 {
+			return None;
+		}
+		Some((from.1 * 8 + from.0, to.1 * 8 + to.0))
+	}
+}
+
+impl Notation for Amazons {
+	// "d1-d7/g7", the amazon's move and then the arrow
+	fn mov_to_string(&self, m: &Self::M) -> String {
+		let sq = |p: u8| square_to_string(p % 10, p / 10);
+		format!("{}-{}/{}", sq(m.0), sq(m.1), sq(m.2))
+	}
+	fn parse_mov(&self, s: &str) -> Option<Self::M> {
+		let (from, rest) = s.trim().split_once('-')?;
+		let (to, arrow) = rest.split_once('/')?;
+		let sq = |s: &str| {
+			let (x, y) = parse_square(s)?;
+			(x < 10 && y < 10).then(|| y * 10 + x)
+		};
+		Some((sq(from)?, sq(to)?, sq(arrow)?))
+	}
+}
+
+impl Notation for Checkers {
+	// squares 1 to 32, "11-15" or every landing square of a jump, "15x24x31"
+	fn mov_to_string(&self, m: &Self::M) -> String {
+		if m.2 == 0 {
+			return format!("{}-{}", m.0 + 1, m.1 + 1);
+		}
+		// the landing squares, found again from the pieces taken
+		fn path(p: u8, to: u8, left: u32, ans: &mut Vec<u8>) -> bool {
+			ans.push(p);
+			if left == 0 && p == to {
+				return true;
+			}
+			let (x, y) = checkers::xy(p);
+			for (dx, dy) in [(1, 1), (-1, 1), (1, -1), (-1, -1)] {
+				if let (Some(mid), Some(q)) = (
+					checkers::square(x + dx, y + dy),
+					checkers::square(x + 2 * dx, y + 2 * dy),
+				) {
+					if left & (1 << mid) != 0 && path(q, to, left & !(1 << mid), ans) {
+						return true;
+					}
+				}
+			}
+			ans.pop();
+			false
+		}
+		let mut squares = vec![];
+		path(m.0, m.1, m.2, &mut squares);
+		squares
+			.iter()
+			.map(|p| (p + 1).to_string())
+			.collect::<Vec<String>>()
+			.join("x")
+	}
+	fn parse_mov(&self, s: &str) -> Option<Self::M> {
+		let s = s.trim();
+		let square = |s: &str| {
+			let p: u8 = s.parse().ok()?;
+			(1..=32).contains(&p).then(|| p - 1)
+		};
+		if let Some((from, to)) = s.split_once('-') {
+			return Some((square(from)?, square(to)?, 0));
+		}
+		let squares = s.split('x').map(square).collect::<Option<Vec<u8>>>()?;
+		if squares.len() < 2 {
+			return None;
+		}
+		let mut captured = 0;
+		for w in squares.windows(2) {
+			captured |= 1 << checkers::jumped(w[0], w[1])?;
+		}
+		Some((squares[0], *squares.last()?, captured))
+	}
+}
+
+impl<const W: usize, const H: usize> Notation for DotsAndBoxes<W, H> {
+	// the two dots of the line, "a1-b1" or "a1-a2", dots go from a1 to the far corner
+	fn mov_to_string(&self, m: &Self::M) -> String {
+		let l = *m as usize;
+		let ((x, y), (dx, dy)) = if l < W * (H + 1) {
+			((l % W, l / W), (1, 0))
+		} else {
+			let l = l - W * (H + 1);
+			((l % (W + 1), l / (W + 1)), (0, 1))
+		};
+		format!(
+			"{}-{}",
+			square_to_string(x as u8, y as u8),
+			square_to_string((x + dx) as u8, (y + dy) as u8)
+		)
+	}
+	fn parse_mov(&self, s: &str) -> Option<Self::M> {
+		let (a, b) = s.trim().split_once('-')?;
+		let (a, b) = (parse_square(a)?, parse_square(b)?);
+		let ((x, y), (bx, by)) = (a.min(b), a.max(b));
+		let (x, y) = (x as usize, y as usize);
+		if bx as usize > W || by as usize > H {
+			return None;
+		}
+		if (bx as usize, by as usize) == (x + 1, y) {
+			Some(Self::horizontal(x, y))
+		} else if (bx as usize, by as usize) == (x, y + 1) {
+			Some(Self::vertical(x, y))
+		} else {
+			None
+		}
+	}
+}
+
+impl Notation for Connect4 {
+	// columns are letters like squares
+	fn mov_to_string(&self, m: &Self::M) -> String {
+		((b'a' + m) as char).to_string()
+	}
+	fn parse_mov(&self, s: &str) -> Option<Self::M> {
+		let s = s.trim();
+		match s.as_bytes() {
+			[c @ b'a'..=b'g'] => Some(c - b'a'),
+			_ => None,
+		}
+	}
+}
+
+impl Notation for old_tictactoe::Tictactoe {
+	fn mov_to_string(&self, m: &Self::M) -> String {
+		square_to_string(m % 3, m / 3)
+	}
+	fn parse_mov(&self, s: &str) -> Option<Self::M> {
+		let (x, y) = parse_square(s)?;
+		if x > 2 || y > 2 {
+			return None;
+		}
+		Some(y * 3 + x)
+	}
+}
+
+impl<const W: usize, const H: usize, const K: usize> Notation for Mnk<W, H, K> {
+	// "h8", swap2's choices are "swap" and "h8+" for MORE
+	fn mov_to_string(&self, m: &Self::M) -> String {
+		if *m == SWAP {
+			return "swap".to_string();
+		}
+		let p = (m & !MORE) as usize;
+		let s = square_to_string((p % W) as u8, (p / W) as u8);
+		if m & MORE != 0 {
+			s + "+"
+		} else {
+			s
+		}
+	}
+	fn parse_mov(&self, s: &str) -> Option<Self::M> {
+		let s = s.trim();
+		if s == "swap" {
+			return Some(SWAP);
+		}
+		let (s, more) = match s.strip_suffix('+') {
+			Some(x) => (x, MORE),
+			None => (s, 0),
+		};
+		let (x, y) = parse_square(s)?;
+		if x as usize >= W || y as usize >= H {
+			return None;
+		}
+		Some((y as usize * W + x as usize) as u16 | more)
+	}
+}
@@ -315,7 +315,7 @@ impl Game for Tablut {
 				[9, 4, 5, 3, 6, 7, 2, 1, 0], // def, lower is better
 				[9, 5, 2, 4, 3, 7, 1, 6, 0], // atk, lower is better
 			];
-			let dif = if m.0 > m.1 { m.0 - m.1 } else { m.1 - m.0 };
+			let dif = m.0.abs_diff(m.1);
 			let dist = if dif >= 9 { dif / 9 } else { dif };
 			if self.turn() {
 				if self.get(m.0) == Tile::K {
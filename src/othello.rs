@@ -1,175 +1,225 @@
 use crate::game::*;
 
-const DIRS: [(i8, i8); 8] = [
-	(0, 1),
-	(-1, 1),
-	(-1, 0),
-	(-1, -1),
-	(0, -1),
-	(1, -1),
-	(1, 0),
-	(1, 1),
+// a square is y * 8 + x, shifting by one of DIRS moves every stone one step,
+// the mask drops the ones that wrapped around to the other side of the board
+const NOT_A: u64 = 0xfefefefefefefefe;
+const NOT_H: u64 = 0x7f7f7f7f7f7f7f7f;
+const DIRS: [(i32, u64); 8] = [
+	(1, NOT_A),
+	(-1, NOT_H),
+	(8, u64::MAX),
+	(-8, u64::MAX),
+	(9, NOT_A),
+	(7, NOT_H),
+	(-7, NOT_A),
+	(-9, NOT_H),
 ];
-const WEIGHTS: [i64; 64] = [
+// how good a square is to have, for move ordering and evals
+pub const WEIGHTS: [i64; 64] = [
 	4, -3, 2, 2, 2, 2, -3, 4, -3, -4, -1, -1, -1, -1, -4, -3, 2, -1, 1, 0, 0, 1, -1, 2, 2, -1, 0, 1,
 	1, 0, -1, 2, 2, -1, 0, 1, 1, 0, -1, 2, 2, -1, 1, 0, 0, 1, -1, 2, -3, -4, -1, -1, -1, -1, -4, -3,
 	4, -3, 2, 2, 2, 2, -3, 4,
 ];
 
+#[inline(always)]
+fn sh(b: u64, s: i32) -> u64 {
+	if s > 0 {
+		b << s
+	} else {
+		b >> -s
+	}
+}
+
+// Kogge-Stone occluded fill: gen and every pro square connected to it in the direction
+#[inline(always)]
+fn fill(mut gen: u64, mut pro: u64, s: i32, mask: u64) -> u64 {
+	pro &= mask;
+	gen |= pro & sh(gen, s);
+	pro &= sh(pro, s);
+	gen |= pro & sh(gen, 2 * s);
+	pro &= sh(pro, 2 * s);
+	gen | (pro & sh(gen, 4 * s))
+}
+
+// squares where own can play
+pub fn moves(own: u64, opp: u64) -> u64 {
+	let empty = !(own | opp);
+	DIRS.iter().fold(0, |ans, &(s, mask)| {
+		ans | (sh(fill(own, opp, s, mask) & opp, s) & mask & empty)
+	})
+}
+
+// stones of opp that own playing on p turns
+pub fn flips(own: u64, opp: u64, p: u8) -> u64 {
+	let b = 1u64 << p;
+	DIRS.iter().fold(0, |ans, &(s, mask)| {
+		let f = fill(b, opp, s, mask);
+		if sh(f, s) & mask & own != 0 {
+			ans | (f & opp)
+		} else {
+			ans
+		}
+	})
+}
+
+// stones of own that can never be flipped: along each of the 4 lines through them
+// the line is full, or on one side there is the edge or another stable stone
+pub fn stable(own: u64, opp: u64) -> u64 {
+	const AXES: [(i32, u64, u64); 4] = [
+		(1, NOT_A, NOT_H),
+		(8, u64::MAX, u64::MAX),
+		(9, NOT_A, NOT_H),
+		(7, NOT_H, NOT_A),
+	];
+	let empty = !(own | opp);
+	let full = AXES
+		.map(|(s, plus, minus)| !(fill(empty, u64::MAX, s, plus) | fill(empty, u64::MAX, -s, minus)));
+	let mut ans = 0;
+	loop {
+		let mut next = own;
+		for (i, &(s, plus, minus)) in AXES.iter().enumerate() {
+			// squares whose neighbour on the minus side is off the board or stable, then the plus side
+			let behind = !(sh(u64::MAX, s) & plus) | (sh(ans, s) & plus);
+			let ahead = !(sh(u64::MAX, -s) & minus) | (sh(ans, -s) & minus);
+			next &= full[i] | behind | ahead;
+		}
+		if next == ans {
+			return ans;
+		}
+		ans = next;
+	}
+}
+
 #[derive(Debug, Hash, Clone, Copy, PartialEq, Eq)]
 pub struct Othello {
-	board: (u64, u64), // .0: cell contains piece or not, .1: piece is black or white (1 for player true, 0 for player false)
+	board: (u64, u64), // .0: stones of player true, .1: stones of player false
 	turn: bool,
-	pass: u8,
+	pass: u8, // passes in a row
 }
 impl Othello {
-	fn add_piece(&mut self, p: u8, c: bool) {
-		self.board.0 |= 1u64 << p;
-		self.board.1 |= (c as u64) << p;
-	}
-	fn flip(&mut self, p: u8) {
-		self.board.1 ^= 1u64 << p;
-	}
 	pub fn has_piece(&self, p: u8) -> bool {
-		(self.board.0 >> p) & 1 != 0
+		((self.board.0 | self.board.1) >> p) & 1 != 0
 	}
+	// true if the stone on p is player true's
 	pub fn get_piece(&self, p: u8) -> bool {
-		(self.board.1 >> p) & 1 != 0
-	}
-	// if can reverse in a certain directon from pos with a direction dir
-	fn reversable(&self, t: bool, mut pos: (i8, i8), dir: (i8, i8)) -> bool {
-		pos.0 += dir.0;
-		pos.1 += dir.1;
-		let mut found = false;
-		while pos.0 < 8 && pos.1 < 8 && pos.0 >= 0 && pos.1 >= 0 {
-			if !self.has_piece(mapc(pos.0 as u8, pos.1 as u8)) {
-				return false;
-			} else if self.get_piece(mapc(pos.0 as u8, pos.1 as u8)) == t {
-				return found;
-			} else {
-				found = true;
-			}
-			pos.0 += dir.0;
-			pos.1 += dir.1;
+		(self.board.0 >> p) & 1 != 0
+	}
+	// stones of player true and of player false
+	pub fn stones(&self) -> (u64, u64) {
+		self.board
+	}
+	// disc difference for player true, at the end empty squares go to the winner
+	pub fn disc_score(&self) -> i64 {
+		let (t, f) = (
+			self.board.0.count_ones() as i64,
+			self.board.1.count_ones() as i64,
+		);
+		let e = 64 - t - f;
+		match t.cmp(&f) {
+			std::cmp::Ordering::Less => t - f - e,
+			std::cmp::Ordering::Equal => 0,
+			std::cmp::Ordering::Greater => t - f + e,
 		}
-		false
-	}
-	// assumes reversable
-	fn reverse(&mut self, t: bool, mut pos: (i8, i8), dir: (i8, i8)) {
-		pos.0 += dir.0;
-		pos.1 += dir.1;
-		while self.get_piece(mapc(pos.0 as u8, pos.1 as u8)) != t {
-			self.flip(mapc(pos.0 as u8, pos.1 as u8));
-			pos.0 += dir.0;
-			pos.1 += dir.1;
+	}
+	// the player to move's stones first
+	fn own_opp(&self) -> (u64, u64) {
+		if self.turn {
+			self.board
+		} else {
+			(self.board.1, self.board.0)
 		}
 	}
 }
-fn mapc(x: u8, y: u8) -> u8 {
-	(y << 3) | x
-}
-fn unmapc(p: u8) -> (u8, u8) {
-	(p & 7, p >> 3)
-}
 impl Game for Othello {
-	type M = u8;
+	type M = u8; // square, 64 to pass
 	type S = (u64, u64, bool);
-	type R = (u64, u64);
+	type R = ((u64, u64), u8);
 	fn new(t: bool) -> Self {
-		let mut ans = Othello {
-			board: (0, 0),
+		Othello {
+			board: ((1 << 27) | (1 << 36), (1 << 28) | (1 << 35)),
 			turn: t,
 			pass: 0,
-		};
-		ans.add_piece(mapc(3, 3), true);
-		ans.add_piece(mapc(4, 4), true);
-		ans.add_piece(mapc(3, 4), false);
-		ans.add_piece(mapc(4, 3), false);
-		ans
+		}
 	}
 	fn turn(&self) -> bool {
 		self.turn
 	}
 	fn get_moves(&self) -> Vec<Self::M> {
-		let mut ans = Vec::<Self::M>::new();
-		for y in 0..8 {
-			for x in 0..8 {
-				if !self.has_piece(mapc(x, y)) {
-					for dir in DIRS.iter() {
-						if self.reversable(self.turn, (x as i8, y as i8), *dir) {
-							ans.push(mapc(x, y));
-							break;
-						}
-					}
-				}
-			}
+		let (own, opp) = self.own_opp();
+		let mut b = moves(own, opp);
+		if b == 0 {
+			return vec![64];
 		}
-		if ans.is_empty() {
-			ans.push(64);
+		let mut ans = Vec::<Self::M>::with_capacity(b.count_ones() as usize);
+		while b != 0 {
+			ans.push(b.trailing_zeros() as u8);
+			b &= b - 1;
 		}
 		ans
 	}
 	fn get_moves_sorted(&self) -> Vec<Self::M> {
 		let mut movs = self.get_moves();
-		movs.sort_by_key(|x| -WEIGHTS[*x as usize]);
+		movs.sort_by_key(|x| -WEIGHTS.get(*x as usize).unwrap_or(&0));
 		movs
 	}
 	fn get_static_state(&self) -> Self::S {
 		(self.board.0, self.board.1, self.turn)
 	}
 	fn state(&self) -> State {
-		if self.board.0 != u64::MAX && self.pass < 2 {
+		if self.board.0 | self.board.1 != u64::MAX && self.pass < 2 {
 			State::Going
 		} else {
-			match self.board.1.count_ones() {
-				0..=31 => State::Lose,
-				32 => State::Draw,
-				_ => State::Win,
+			match self.disc_score().cmp(&0) {
+				std::cmp::Ordering::Less => State::Lose,
+				std::cmp::Ordering::Equal => State::Draw,
+				std::cmp::Ordering::Greater => State::Win,
 			}
 		}
 	}
 	fn mov(&mut self, m: &u8) {
 		if *m != 64 {
-			let (x, y) = unmapc(*m);
-			for dir in DIRS.iter() {
-				if self.reversable(self.turn, (x as i8, y as i8), *dir) {
-					self.reverse(self.turn, (x as i8, y as i8), *dir);
-				}
-			}
-			self.add_piece(*m, self.turn);
+			let (own, opp) = self.own_opp();
+			let f = flips(own, opp, *m);
+			let (own, opp) = (own | f | (1u64 << m), opp & !f);
+			self.board = if self.turn { (own, opp) } else { (opp, own) };
+			self.pass = 0;
 		} else {
 			self.pass += 1;
 		}
 		self.turn = !self.turn;
 	}
 	fn mov_with_rollback(&mut self, m: &u8) -> Self::R {
-		let ans = self.board;
+		let ans = (self.board, self.pass);
 		self.mov(m);
 		ans
 	}
 	fn rollback(&mut self, rb: Self::R) {
-		self.board = rb;
-		if self.pass != 0 {
-			self.pass -= 1;
-		}
+		(self.board, self.pass) = rb;
 		self.turn = !self.turn;
 	}
+	fn score(&self) -> Option<i64> {
+		match self.state() {
+			State::Going => None,
+			_ => Some(self.disc_score()),
+		}
+	}
 }
 impl std::fmt::Display for Othello {
 	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
 		writeln!(
 			f,
 			"{} - {}",
-			(self.board.0 & self.board.1).count_ones(),
-			(self.board.0 & !self.board.1).count_ones()
+			self.board.0.count_ones(),
+			self.board.1.count_ones()
 		)?;
 		for y in 0..8 {
 			for x in 0..8 {
+				let p = (y << 3) | x;
 				write!(
 					f,
 					"{}",
-					match self.has_piece(mapc(x, y)) {
-						true => match self.get_piece(mapc(x, y)) {
+					match self.has_piece(p) {
+						true => match self.get_piece(p) {
 							true => 'O',
 							false => 'X',
 						},
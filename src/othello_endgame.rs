@@ -0,0 +1,127 @@
+// Exact endgame solver for Othello: negamax on the final disc difference
+// with parity and fastest-first move ordering and stability cut-offs.
+use crate::game::*;
+use crate::othello::*;
+use std::time::Instant;
+
+// positions with at most this many empty squares are worth solving
+pub const SOLVE_EMPTIES: u32 = 20;
+// with more empties than this moves are ordered by the opponent's mobility first
+const FASTEST_FIRST: u32 = 7;
+// stability is only worth computing with more empties than this
+const STABILITY: u32 = 3;
+const QUADRANTS: [u64; 4] = [
+	0x000000000f0f0f0f,
+	0x00000000f0f0f0f0,
+	0x0f0f0f0f00000000,
+	0xf0f0f0f000000000,
+];
+
+struct Solver {
+	nodes: u64,
+	deadline: Instant,
+	timed_out: bool,
+}
+
+impl Solver {
+	// moves of own, best looking first
+	fn order(&self, own: u64, opp: u64, mut mv: u64, empty: u64) -> ([(u32, u8); 32], usize) {
+		// an odd region lets the player moving into it also get the last move there
+		let odd = QUADRANTS
+			.iter()
+			.filter(|q| (empty & **q).count_ones() & 1 == 1)
+			.fold(0, |a, q| a | q);
+		let fastest = empty.count_ones() > FASTEST_FIRST;
+		let mut ans = [(0, 0); 32];
+		let mut n = 0;
+		while mv != 0 && n < 32 {
+			let m = mv.trailing_zeros() as u8;
+			mv &= mv - 1;
+			let b = 1u64 << m;
+			let parity = (odd & b == 0) as u32;
+			let key = if fastest {
+				let f = flips(own, opp, m);
+				moves(opp & !f, own | f | b).count_ones() * 2 + parity
+			} else {
+				parity
+			};
+			ans[n] = (key, m);
+			n += 1;
+		}
+		ans[..n].sort_unstable_by_key(|x| x.0);
+		(ans, n)
+	}
+	// final disc difference for own with best play, fail hard within alpha..beta
+	fn negamax(&mut self, own: u64, opp: u64, mut alpha: i32, beta: i32, passed: bool) -> i32 {
+		self.nodes += 1;
+		if self.nodes & 4095 == 0 && Instant::now() > self.deadline {
+			self.timed_out = true;
+		}
+		if self.timed_out {
+			return alpha;
+		}
+		let empty = !(own | opp);
+		let mv = moves(own, opp);
+		if mv == 0 {
+			if passed || empty == 0 {
+				// empty squares go to the winner
+				let d = own.count_ones() as i32 - opp.count_ones() as i32;
+				return d + d.signum() * empty.count_ones() as i32;
+			}
+			return -self.negamax(opp, own, -beta, -alpha, true);
+		}
+		if empty.count_ones() > STABILITY {
+			// own can't end with more than what opp can never lose
+			let upper = 64 - 2 * stable(opp, own).count_ones() as i32;
+			if upper <= alpha {
+				return upper;
+			}
+		}
+		let (ms, n) = self.order(own, opp, mv, empty);
+		for &(_, m) in ms[..n].iter() {
+			let f = flips(own, opp, m);
+			let v = -self.negamax(opp & !f, own | f | (1u64 << m), -beta, -alpha, false);
+			if v > alpha {
+				alpha = v;
+				if alpha >= beta {
+					break;
+				}
+			}
+		}
+		alpha
+	}
+}
+
+// the best move and the final score (Othello::disc_score) for player true with best play,
+// None if the deadline comes first
+pub fn solve(g: &Othello, deadline: Instant) -> Option<(u8, i64)> {
+	if g.state() != State::Going {
+		return None;
+	}
+	let (t, f) = g.stones();
+	let (own, opp) = if g.turn() { (t, f) } else { (f, t) };
+	let mut s = Solver {
+		nodes: 0,
+		deadline,
+		timed_out: false,
+	};
+	let mv = moves(own, opp);
+	let (m, v) = if mv == 0 {
+		(64, -s.negamax(opp, own, -64, 64, true))
+	} else {
+		let (ms, n) = s.order(own, opp, mv, !(own | opp));
+		let mut best = (ms[0].1, -65);
+		for &(_, m) in ms[..n].iter() {
+			let f = flips(own, opp, m);
+			let v = -s.negamax(opp & !f, own | f | (1u64 << m), -64, -best.1, false);
+			if v > best.1 {
+				best = (m, v);
+			}
+		}
+		best
+	};
+	if s.timed_out {
+		return None;
+	}
+	Some((m, if g.turn() { v as i64 } else { -v as i64 }))
+}
@@ -0,0 +1,144 @@
+// Oware (Abapa): no stores in the sowing, the origin house is left out when sowing
+// around the board, the last seed making 2 or 3 in an opponent house takes it along
+// with the houses before it that also have 2 or 3, unless that would take everything
+// the opponent has. A player must give seeds to an opponent with none, when no move
+// can the game ends and everyone takes the seeds on their side.
+use crate::game::*;
+use crate::mancala::sow;
+use std::cmp::Ordering;
+
+// moves in a row without a capture after which the game ends as if nobody could move,
+// so that the endless cycles of the last few seeds finish
+pub const QUIET_MOVES: u8 = 100;
+
+/*
+ b a 9 8 7 6
+ 0 1 2 3 4 5
+*/
+#[derive(Debug, Hash, Clone, Copy, PartialEq, Eq)]
+pub struct Oware {
+	pub a: [u8; 12],
+	// seeds taken by player true and by player false
+	pub store: [u8; 2],
+	turn: bool,
+	quiet: u8,
+}
+
+impl Oware {
+	// houses of player t
+	fn side(t: bool) -> std::ops::Range<usize> {
+		if t {
+			0..6
+		} else {
+			6..12
+		}
+	}
+	fn seeds(&self, t: bool) -> u8 {
+		self.a[Self::side(t)].iter().sum()
+	}
+	fn legal(&self, i: usize) -> bool {
+		// with the opponent starving the seeds have to reach their side
+		self.a[i] != 0
+			&& (self.seeds(!self.turn) != 0 || i + self.a[i] as usize >= Self::side(self.turn).end)
+	}
+}
+
+impl Game for Oware {
+	type M = u8;
+	type S = ([u8; 12], [u8; 2], bool);
+	type R = ([u8; 12], [u8; 2], u8);
+	fn new(t: bool) -> Self {
+		Oware {
+			a: [4; 12],
+			store: [0; 2],
+			turn: t,
+			quiet: 0,
+		}
+	}
+	fn turn(&self) -> bool {
+		self.turn
+	}
+	fn get_moves(&self) -> Vec<u8> {
+		Self::side(self.turn)
+			.rev()
+			.filter(|i| self.legal(*i))
+			.map(|i| i as u8)
+			.collect()
+	}
+	fn get_moves_sorted(&self) -> Vec<u8> {
+		self.get_moves()
+	}
+	fn get_static_state(&self) -> Self::S {
+		(self.a, self.store, self.turn)
+	}
+	fn state(&self) -> State {
+		if self.store[0] > 24 {
+			State::Win
+		} else if self.store[1] > 24 {
+			State::Lose
+		} else if self.quiet >= QUIET_MOVES || !Self::side(self.turn).any(|i| self.legal(i)) {
+			let t = self.store[0] + self.seeds(true);
+			let f = self.store[1] + self.seeds(false);
+			match t.cmp(&f) {
+				Ordering::Greater => State::Win,
+				Ordering::Less => State::Lose,
+				Ordering::Equal => State::Draw,
+			}
+		} else {
+			State::Going
+		}
+	}
+	fn mov(&mut self, m: &u8) {
+		let from = *m as usize;
+		let last = sow(&mut self.a, from, |i| i == from);
+		let opp = Self::side(!self.turn);
+		let mut taken = 0;
+		let mut i = last;
+		while opp.contains(&i) && (2..=3).contains(&self.a[i]) {
+			taken += self.a[i];
+			if i == opp.start {
+				break;
+			}
+			i -= 1;
+		}
+		// a grand slam takes nothing
+		if taken != 0 && taken != self.seeds(!self.turn) {
+			let mut i = last;
+			while opp.contains(&i) && (2..=3).contains(&self.a[i]) {
+				self.a[i] = 0;
+				if i == opp.start {
+					break;
+				}
+				i -= 1;
+			}
+			self.store[!self.turn as usize] += taken;
+			self.quiet = 0;
+		} else {
+			self.quiet += 1;
+		}
+		self.turn = !self.turn;
+	}
+	fn mov_with_rollback(&mut self, m: &u8) -> Self::R {
+		let rb = (self.a, self.store, self.quiet);
+		self.mov(m);
+		rb
+	}
+	fn rollback(&mut self, rb: Self::R) {
+		(self.a, self.store, self.quiet) = rb;
+		self.turn = !self.turn;
+	}
+}
+
+impl std::fmt::Display for Oware {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		for i in (6..12).rev() {
+			write!(f, " {}", self.a[i])?;
+		}
+		writeln!(f, "   {}", self.store[1])?;
+		for i in 0..6 {
+			write!(f, " {}", self.a[i])?;
+		}
+		writeln!(f, "   {}", self.store[0])?;
+		Ok(())
+	}
+}
@@ -0,0 +1,408 @@
+// Line based engine protocol, in the spirit of UCI/GTP.
+//
+// commands:
+//   isready                             -> readyok
+//   games                               -> games <name>...
+//   game <name>                         select the game, resets the position
+//   agents                              -> agents <name>...
+//   agent <name>                        select the agent from the game's registry
+//   weights <path>                      load the weights of the "<agent>:feature" agents
+//   position startpos [first|second] [moves <m>...]
+//                                       first (default) means player true moves first
+//   move <m>                            play a move on the current position
+//   go [movetime <ms>] [ttime <ms>] [ftime <ms>] [tinc <ms>] [finc <ms>] [infinite]
+//                                       -> info depth <d> score <s> nodes <n> time <ms> pv <m>...
+//                                       -> bestmove <m>
+//                                       ttime/tinc are player true's clock, ftime/finc player false's
+//   stop                                make a running go return as soon as possible
+//   quit
+// anything wrong gets an "error <what>" line, moves use each game's Notation
+use crate::ai::*;
+use crate::amazons::Amazons;
+use crate::breakthrough::Breakthrough;
+use crate::checkers::Checkers;
+use crate::connect4::Connect4;
+use crate::dots_and_boxes::Dots5;
+use crate::game::*;
+use crate::go::*;
+use crate::hex::Hex;
+use crate::mancala::Mancala;
+use crate::mnk::*;
+use crate::notation::Notation;
+use crate::othello::Othello;
+use crate::oware::Oware;
+use crate::registry::*;
+use crate::tablut::Tablut;
+use crate::tafl::*;
+use crate::ultimate_tictactoe::UltimateTictactoe;
+use std::io::BufRead;
+use std::io::Write;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
+use std::sync::mpsc::channel;
+use std::sync::mpsc::Receiver;
+use std::sync::Arc;
+use std::time::Duration;
+use std::time::Instant;
+
+pub const GAMES: [&str; 16] = [
+	"tablut",
+	"othello",
+	"mancala",
+	"tictactoe",
+	"connect4",
+	"brandubh",
+	"copenhagen",
+	"oware",
+	"gomoku",
+	"ultimate_tictactoe",
+	"go",
+	"hex",
+	"breakthrough",
+	"amazons",
+	"checkers",
+	"dots_and_boxes",
+];
+const DEFAULT_MOVETIME: Duration = Duration::from_millis(1000);
+
+enum Next {
+	Game(String),
+	Quit,
+}
+
+// the flag the agents watch, set by the reader as soon as it reads a stop line.
+// A go only starts stopped if the reader has read more stop lines than the commands got
+// to, that is if a stop came after it, so a stop with nothing running doesn't linger
+#[derive(Clone, Default)]
+struct Stop {
+	flag: Arc<AtomicBool>,
+	read: Arc<AtomicUsize>,
+	handled: Arc<AtomicUsize>,
+}
+
+impl Stop {
+	fn sync(&self) {
+		let pending = self.read.load(Ordering::SeqCst) > self.handled.load(Ordering::SeqCst);
+		self.flag.store(pending, Ordering::SeqCst);
+	}
+	// a stop line got to the commands
+	fn handle(&self) {
+		self.handled.fetch_add(1, Ordering::SeqCst);
+		self.sync();
+	}
+}
+
+// lines are read on their own thread so that a stop can reach a running search
+fn spawn_reader<R: BufRead + Send + 'static>(input: R, stop: Stop) -> Receiver<String> {
+	let (tx, rx) = channel();
+	std::thread::spawn(move || {
+		for line in input.lines() {
+			let line = match line {
+				Ok(x) => x,
+				Err(_) => break,
+			};
+			if line.trim() == "stop" {
+				stop.read.fetch_add(1, Ordering::SeqCst);
+				stop.flag.store(true, Ordering::SeqCst);
+			}
+			if tx.send(line).is_err() {
+				break;
+			}
+		}
+	});
+	rx
+}
+
+// runs until quit or the end of the input, with any of the GAMES
+pub fn run<R: BufRead + Send + 'static, W: Write>(input: R, mut output: W) {
+	let stop = Stop::default();
+	let rx = spawn_reader(input, stop.clone());
+	let mut next = idle(&rx, &mut output, &stop);
+	while let Next::Game(name) = next {
+		next = match name.as_str() {
+			"tablut" => Session::<Tablut>::new(stop.clone()).run(&rx, &mut output),
+			"othello" => Session::<Othello>::new(stop.clone()).run(&rx, &mut output),
+			"mancala" => Session::<Mancala>::new(stop.clone()).run(&rx, &mut output),
+			"tictactoe" => Session::<Tictactoe>::new(stop.clone()).run(&rx, &mut output),
+			"connect4" => Session::<Connect4>::new(stop.clone()).run(&rx, &mut output),
+			"brandubh" => Session::<Brandubh>::new(stop.clone()).run(&rx, &mut output),
+			"copenhagen" => Session::<Copenhagen>::new(stop.clone()).run(&rx, &mut output),
+			"oware" => Session::<Oware>::new(stop.clone()).run(&rx, &mut output),
+			"gomoku" => Session::<Gomoku>::new(stop.clone()).run(&rx, &mut output),
+			"ultimate_tictactoe" => Session::<UltimateTictactoe>::new(stop.clone()).run(&rx, &mut output),
+			"go" => Session::<Go9>::new(stop.clone()).run(&rx, &mut output),
+			"hex" => Session::<Hex<11>>::new(stop.clone()).run(&rx, &mut output),
+			"breakthrough" => Session::<Breakthrough>::new(stop.clone()).run(&rx, &mut output),
+			"amazons" => Session::<Amazons>::new(stop.clone()).run(&rx, &mut output),
+			"checkers" => Session::<Checkers>::new(stop.clone()).run(&rx, &mut output),
+			"dots_and_boxes" => Session::<Dots5>::new(stop.clone()).run(&rx, &mut output),
+			_ => unreachable!(),
+		};
+	}
+}
+
+// runs until quit or the end of the input, with a single game
+pub fn run_game<G, R, W>(input: R, mut output: W)
+where
+	G: Registered + Notation,
+	R: BufRead + Send + 'static,
+	W: Write,
+{
+	let stop = Stop::default();
+	let rx = spawn_reader(input, stop.clone());
+	let mut session = Session::<G>::new(stop);
+	while let Next::Game(_) = session.run(&rx, &mut output) {
+		let _ = writeln!(
+			output,
+			"error only {} is available",
+			std::any::type_name::<G>()
+		);
+		session.reset(true, vec![]);
+	}
+}
+
+fn select_game<W: Write>(name: &str, output: &mut W) -> Option<Next> {
+	if GAMES.contains(&name) {
+		Some(Next::Game(name.to_string()))
+	} else {
+		let _ = writeln!(output, "error unknown game {}", name);
+		None
+	}
+}
+
+// before any game is selected
+fn idle<W: Write>(rx: &Receiver<String>, output: &mut W, stop: &Stop) -> Next {
+	for line in rx.iter() {
+		let mut words = line.split_whitespace();
+		match words.next() {
+			None => {}
+			Some("quit") => return Next::Quit,
+			Some("isready") => {
+				let _ = writeln!(output, "readyok");
+			}
+			Some("games") => {
+				let _ = writeln!(output, "games {}", GAMES.join(" "));
+			}
+			Some("game") => {
+				if let Some(next) = select_game(words.next().unwrap_or(""), output) {
+					return next;
+				}
+			}
+			Some("stop") => stop.handle(),
+			Some(cmd) => {
+				let _ = writeln!(output, "error no game selected for {}", cmd);
+			}
+		}
+		let _ = output.flush();
+	}
+	Next::Quit
+}
+
+struct Session<G: Game> {
+	registry: Registry<G>,
+	agent_name: String,
+	agent: Box<dyn Ai<G>>,
+	g: G,
+	t0: bool,
+	moves: Vec<G::M>,
+	stop: Stop,
+}
+
+impl<G: Registered + Notation> Session<G> {
+	fn new(stop: Stop) -> Self {
+		let registry = G::registry();
+		let agent_name = if registry.contains("minimax_final:default") {
+			"minimax_final:default".to_string()
+		} else {
+			registry.names().next().unwrap().to_string()
+		};
+		let mut agent = registry.create(&agent_name, true).unwrap();
+		agent.set_stop(stop.flag.clone());
+		Self {
+			registry,
+			agent_name,
+			agent,
+			g: G::new(true),
+			t0: true,
+			moves: vec![],
+			stop,
+		}
+	}
+	// agents can't go back, so they are rebuilt and fed the moves again
+	fn reset(&mut self, t0: bool, moves: Vec<G::M>) {
+		self.t0 = t0;
+		self.g = G::new(t0);
+		self.agent = self.registry.create(&self.agent_name, t0).unwrap();
+		self.agent.set_stop(self.stop.flag.clone());
+		for m in moves.iter() {
+			self.g.mov(m);
+			self.agent.mov(m);
+		}
+		self.moves = moves;
+	}
+	fn play(&mut self, s: &str) -> Result<(), String> {
+		if self.g.state() != State::Going {
+			return Err("game is over".to_string());
+		}
+		match self.g.parse_mov(s) {
+			Some(m) if self.g.get_moves().contains(&m) => {
+				self.g.mov(&m);
+				self.agent.mov(&m);
+				self.moves.push(m);
+				Ok(())
+			}
+			Some(_) => Err(format!("illegal move {}", s)),
+			None => Err(format!("can't read move {}", s)),
+		}
+	}
+	fn position<'a, I: Iterator<Item = &'a str>>(&mut self, mut words: I) -> Result<(), String> {
+		if words.next() != Some("startpos") {
+			return Err("position needs startpos".to_string());
+		}
+		let mut t0 = true;
+		let mut w = words.next();
+		match w {
+			Some("first") => w = words.next(),
+			Some("second") => {
+				t0 = false;
+				w = words.next();
+			}
+			_ => {}
+		}
+		self.reset(t0, vec![]);
+		match w {
+			None => Ok(()),
+			Some("moves") => words.try_for_each(|s| self.play(s)),
+			Some(x) => Err(format!("unexpected {}", x)),
+		}
+	}
+	fn time_limit<'a, I: Iterator<Item = &'a str>>(&self, mut words: I) -> Result<Duration, String> {
+		let mut movetime = None;
+		let mut clock = [None; 2];
+		let mut inc = [Duration::ZERO; 2];
+		while let Some(w) = words.next() {
+			if w == "infinite" {
+				movetime = Some(Duration::from_secs(365 * 24 * 3600));
+				continue;
+			}
+			let ms = words
+				.next()
+				.and_then(|x| x.parse::<u64>().ok())
+				.map(Duration::from_millis)
+				.ok_or(format!("{} needs a time in milliseconds", w))?;
+			match w {
+				"movetime" => movetime = Some(ms),
+				"ttime" => clock[0] = Some(ms),
+				"ftime" => clock[1] = Some(ms),
+				"tinc" => inc[0] = ms,
+				"finc" => inc[1] = ms,
+				_ => return Err(format!("unknown time control {}", w)),
+			}
+		}
+		let side = if self.g.turn() { 0 } else { 1 };
+		Ok(match (movetime, clock[side]) {
+			(Some(x), _) => x,
+			// a slice of the clock, never all of it
+			(None, Some(c)) => (c / 20 + inc[side]).min(c.saturating_sub(Duration::from_millis(50))),
+			(None, None) => DEFAULT_MOVETIME,
+		})
+	}
+	fn go<W: Write>(&mut self, tl: Duration, output: &mut W) -> Result<(), String> {
+		if self.g.state() != State::Going {
+			return Err("game is over".to_string());
+		}
+		self.stop.sync();
+		let st = Instant::now();
+		let mut m = self.agent.get_mov(tl);
+		let moves = self.g.get_moves();
+		// a search stopped right away may not have a move yet
+		if !moves.contains(&m) {
+			m = moves[0];
+		}
+		if let Some(info) = self.agent.info() {
+			// only the part of the pv that can actually be played
+			let mut g = self.g.clone();
+			let mut pv = vec![];
+			for x in info.pv.iter() {
+				if g.state() != State::Going || !g.get_moves().contains(x) {
+					break;
+				}
+				pv.push(g.mov_to_string(x));
+				g.mov(x);
+			}
+			let _ = writeln!(
+				output,
+				"info depth {} score {} nodes {} time {} pv {}",
+				info.depth,
+				info.score,
+				info.nodes,
+				st.elapsed().as_millis(),
+				pv.join(" ")
+			);
+		}
+		let _ = writeln!(output, "bestmove {}", self.g.mov_to_string(&m));
+		Ok(())
+	}
+	fn run<W: Write>(&mut self, rx: &Receiver<String>, output: &mut W) -> Next {
+		for line in rx.iter() {
+			let mut words = line.split_whitespace();
+			let res = match words.next() {
+				None => Ok(()),
+				Some("quit") => return Next::Quit,
+				Some("isready") => {
+					let _ = writeln!(output, "readyok");
+					Ok(())
+				}
+				Some("games") => {
+					let _ = writeln!(output, "games {}", GAMES.join(" "));
+					Ok(())
+				}
+				Some("game") => {
+					if let Some(next) = select_game(words.next().unwrap_or(""), output) {
+						return next;
+					}
+					Ok(())
+				}
+				Some("agents") => {
+					let _ = writeln!(
+						output,
+						"agents {}",
+						self.registry.names().collect::<Vec<&str>>().join(" ")
+					);
+					Ok(())
+				}
+				Some("agent") => match words.next() {
+					Some(name) if self.registry.contains(name) => {
+						self.agent_name = name.to_string();
+						let moves = std::mem::take(&mut self.moves);
+						self.reset(self.t0, moves);
+						Ok(())
+					}
+					x => Err(format!("unknown agent {}", x.unwrap_or(""))),
+				},
+				Some("weights") => match words.next() {
+					Some(path) => self.registry.load_weights(path),
+					None => Err("weights needs a file".to_string()),
+				},
+				Some("position") => self.position(words),
+				Some("move") => match words.next() {
+					Some(s) => self.play(s),
+					None => Err("move needs a move".to_string()),
+				},
+				Some("go") => self.time_limit(words).and_then(|tl| self.go(tl, output)),
+				Some("stop") => {
+					// nothing is running anymore
+					self.stop.handle();
+					Ok(())
+				}
+				Some(cmd) => Err(format!("unknown command {}", cmd)),
+			};
+			if let Err(e) = res {
+				let _ = writeln!(output, "error {}", e);
+			}
+			let _ = output.flush();
+		}
+		Next::Quit
+	}
+}
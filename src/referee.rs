@@ -0,0 +1,163 @@
+// Referee for matches between engines speaking the text protocol over tcp, on localhost only.
+// The first client to connect plays player true, the second player false.
+// Both get "game <name>" and "position startpos first|second", then every move
+// as "move <m>", and the player to move gets "go movetime <ms>".
+use crate::game::*;
+use crate::notation::Notation;
+use std::io::BufRead;
+use std::io::BufReader;
+use std::io::Write;
+use std::net::TcpListener;
+use std::net::TcpStream;
+use std::net::ToSocketAddrs;
+use std::time::Duration;
+use std::time::Instant;
+
+// allowed on top of the time limit for the network and the engine's overhead
+pub const GRACE: Duration = Duration::from_millis(500);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum End {
+	Finished,
+	// the player (true/false) lost because of
+	IllegalMove(bool),
+	Timeout(bool),
+	Disconnected(bool),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MatchRecord<M> {
+	pub moves: Vec<M>,
+	pub state: State,
+	pub end: End,
+	// Game::score of the final position, None if the game didn't finish
+	pub score: Option<i64>,
+}
+
+struct Client {
+	reader: BufReader<TcpStream>,
+	writer: TcpStream,
+}
+
+impl Client {
+	fn send(&mut self, s: &str) -> bool {
+		writeln!(self.writer, "{}", s).is_ok() && self.writer.flush().is_ok()
+	}
+	// skips info and anything else until a bestmove
+	fn bestmove(&mut self, side: bool, deadline: Instant) -> Result<String, End> {
+		loop {
+			let left = deadline
+				.checked_duration_since(Instant::now())
+				.ok_or(End::Timeout(side))?;
+			let _ = self.reader.get_ref().set_read_timeout(Some(left));
+			let mut line = String::new();
+			match self.reader.read_line(&mut line) {
+				Ok(0) => return Err(End::Disconnected(side)),
+				Ok(_) => {
+					if let Some(m) = line.trim().strip_prefix("bestmove ") {
+						return Ok(m.to_string());
+					}
+				}
+				Err(_) => return Err(End::Timeout(side)),
+			}
+		}
+	}
+}
+
+fn lost(t: bool) -> State {
+	if t {
+		State::Lose
+	} else {
+		State::Win
+	}
+}
+
+// accepts two clients on the listener and plays a game of G between them
+// game is the name the clients know G by, t is who moves first, tl the time per move
+pub fn referee<G: Notation>(
+	listener: &TcpListener,
+	game: &str,
+	t: bool,
+	tl: Duration,
+) -> std::io::Result<MatchRecord<G::M>> {
+	let mut clients = vec![];
+	for _ in 0..2 {
+		let (stream, _) = listener.accept()?;
+		stream.set_nodelay(true)?;
+		clients.push(Client {
+			reader: BufReader::new(stream.try_clone()?),
+			writer: stream,
+		});
+	}
+	let start = format!("position startpos {}", if t { "first" } else { "second" });
+	for c in clients.iter_mut() {
+		c.send(&format!("game {}", game));
+		c.send(&start);
+	}
+	let mut g = G::new(t);
+	let mut moves = vec![];
+	let mut end = End::Finished;
+	while g.state() == State::Going {
+		let side = g.turn();
+		let c = &mut clients[if side { 0 } else { 1 }];
+		let st = Instant::now();
+		let answer = if c.send(&format!("go movetime {}", tl.as_millis())) {
+			c.bestmove(side, st + tl + GRACE)
+		} else {
+			Err(End::Disconnected(side))
+		};
+		end = match answer {
+			Err(e) => e,
+			Ok(s) => match g.parse_mov(&s) {
+				Some(m) if g.get_moves().contains(&m) => {
+					let line = format!("move {}", g.mov_to_string(&m));
+					for c in clients.iter_mut() {
+						c.send(&line);
+					}
+					g.mov(&m);
+					moves.push(m);
+					continue;
+				}
+				_ => End::IllegalMove(side),
+			},
+		};
+		break;
+	}
+	for c in clients.iter_mut() {
+		c.send("quit");
+	}
+	let (state, score) = match end {
+		End::Finished => (g.state(), g.score()),
+		End::IllegalMove(x) | End::Timeout(x) | End::Disconnected(x) => (lost(x), None),
+	};
+	eprintln!(
+		"referee: {} after {} moves, {:?}{}",
+		match state {
+			State::Win => "1-0",
+			State::Lose => "0-1",
+			_ => "1/2-1/2",
+		},
+		moves.len(),
+		end,
+		score.map(|s| format!(", score {}", s)).unwrap_or_default()
+	);
+	Ok(MatchRecord {
+		moves,
+		state,
+		end,
+		score,
+	})
+}
+
+// a listener for referee, on localhost only, port 0 picks a free one
+pub fn listen(port: u16) -> std::io::Result<TcpListener> {
+	TcpListener::bind(("127.0.0.1", port))
+}
+
+// connects to a referee and answers it with protocol::run until it says quit
+pub fn join<A: ToSocketAddrs>(addr: A) -> std::io::Result<()> {
+	let stream = TcpStream::connect(addr)?;
+	stream.set_nodelay(true)?;
+	crate::protocol::run(BufReader::new(stream.try_clone()?), stream);
+	Ok(())
+}
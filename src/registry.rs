@@ -0,0 +1,278 @@
+use crate::ai::Ai;
+use crate::amazons::Amazons;
+use crate::breakthrough::Breakthrough;
+use crate::checkers::Checkers;
+use crate::connect4::Connect4;
+use crate::default_heuristic::DefaultHeuristic;
+use crate::dots_and_boxes::DotsAndBoxes;
+use crate::feature_heuristic::*;
+use crate::game::*;
+use crate::go::*;
+use crate::heuristic::Heuristic;
+use crate::hex::Hex;
+use crate::mancala::Mancala;
+use crate::minimax_final::MinimaxFinal;
+use crate::minimax_fixed::MinimaxFixed;
+use crate::minimax_hard::MinimaxHard;
+use crate::minimax_killer::MinimaxKiller;
+use crate::minimax_killer_b::MinimaxKillerB;
+use crate::minimax_simple::MinimaxSimple;
+use crate::mnk::Mnk;
+use crate::monte_carlo_total::MonteCarloTotal;
+use crate::monte_carlo_tree_search::MonteCarloTreeSearch;
+use crate::nim::*;
+use crate::old_tictactoe;
+use crate::othello::Othello;
+use crate::oware::Oware;
+use crate::random_agent::RandomAgent;
+use crate::tablut::Tablut;
+use crate::tablut_heuristics::*;
+use crate::tafl::*;
+use crate::ultimate_tictactoe::UltimateTictactoe;
+
+pub type Constructor<G> = fn(bool) -> Box<dyn Ai<G>>;
+pub type WeightsLoader = fn(&str) -> Result<(), String>;
+
+fn construct<G: Game, A: Ai<G> + 'static>(t: bool) -> Box<dyn Ai<G>> {
+	Box::new(A::new(t))
+}
+
+// maps names like "minimax_final:default" or "mcts" to agent constructors for a game
+pub struct Registry<G: Game> {
+	entries: Vec<(String, Constructor<G>)>,
+	// sets the weights of the "feature" agents from a file
+	weights: Option<WeightsLoader>,
+}
+
+impl<G: Game> Default for Registry<G> {
+	fn default() -> Self {
+		Self {
+			entries: vec![],
+			weights: None,
+		}
+	}
+}
+
+impl<G: Game + 'static> Registry<G> {
+	pub fn new() -> Self {
+		Self::default()
+	}
+	// registering a name twice replaces the old constructor
+	pub fn register<A: Ai<G> + 'static>(&mut self, name: &str) -> &mut Self {
+		self.register_fn(name, construct::<G, A>)
+	}
+	pub fn register_fn(&mut self, name: &str, f: Constructor<G>) -> &mut Self {
+		match self.entries.iter_mut().find(|x| x.0 == name) {
+			Some(x) => x.1 = f,
+			None => self.entries.push((name.to_string(), f)),
+		}
+		self
+	}
+	// agents that don't need a heuristic
+	pub fn register_basic(&mut self) -> &mut Self {
+		self
+			.register::<RandomAgent<G>>("random")
+			.register::<MonteCarloTotal<G>>("monte_carlo_total")
+			.register::<MonteCarloTreeSearch<G>>("mcts")
+	}
+	// every minimax agent with heuristic H, as "<agent>:<h>"
+	pub fn register_minimax<H: Heuristic<G> + 'static>(&mut self, h: &str) -> &mut Self {
+		self
+			.register::<MinimaxSimple<G, H>>(&format!("minimax_simple:{}", h))
+			.register::<MinimaxHard<G, H>>(&format!("minimax_hard:{}", h))
+			.register::<MinimaxKiller<G, H>>(&format!("minimax_killer:{}", h))
+			.register::<MinimaxKillerB<G, H>>(&format!("minimax_killer_b:{}", h))
+			.register::<MinimaxFinal<G, H>>(&format!("minimax_final:{}", h))
+			.register::<MinimaxFixed<G, H, 2>>(&format!("minimax_fixed:{}:2", h))
+			.register::<MinimaxFixed<G, H, 4>>(&format!("minimax_fixed:{}:4", h))
+			.register::<MinimaxFixed<G, H, 6>>(&format!("minimax_fixed:{}:6", h))
+	}
+	// the minimax agents with the weights of slot 0, as "<agent>:feature"
+	pub fn register_features(&mut self) -> &mut Self
+	where
+		G: Features,
+	{
+		self.weights = Some(|path| load_weights::<G>(0, path));
+		self.register_minimax::<FeatureHeuristic<0>>("feature")
+	}
+	pub fn load_weights(&self, path: &str) -> Result<(), String> {
+		match self.weights {
+			Some(f) => f(path),
+			None => Err("no feature agents for this game".to_string()),
+		}
+	}
+	pub fn names(&self) -> impl Iterator<Item = &str> {
+		self.entries.iter().map(|x| x.0.as_str())
+	}
+	pub fn contains(&self, name: &str) -> bool {
+		self.entries.iter().any(|x| x.0 == name)
+	}
+	pub fn create(&self, name: &str, t: bool) -> Option<Box<dyn Ai<G>>> {
+		self.entries.iter().find(|x| x.0 == name).map(|x| (x.1)(t))
+	}
+}
+
+// games that know which agents can play them
+pub trait Registered: Game + 'static {
+	fn registry() -> Registry<Self>;
+}
+
+impl Registered for Tablut {
+	fn registry() -> Registry<Self> {
+		let mut r = Registry::new();
+		r.register_basic()
+			.register_minimax::<DefaultHeuristic>("default")
+			.register_minimax::<FmHeuristic>("fm")
+			.register_minimax::<BuggedHeuristic>("bugged")
+			.register_minimax::<VpHeuristic>("vp")
+			.register_features();
+		r
+	}
+}
+
+impl<V: Variant> Registered for Tafl<V> {
+	fn registry() -> Registry<Self> {
+		let mut r = Registry::new();
+		r.register_basic()
+			.register_minimax::<DefaultHeuristic>("default");
+		r
+	}
+}
+
+impl Registered for Othello {
+	fn registry() -> Registry<Self> {
+		let mut r = Registry::new();
+		r.register_basic()
+			.register_minimax::<DefaultHeuristic>("default")
+			.register_features();
+		r
+	}
+}
+
+impl Registered for Mancala {
+	fn registry() -> Registry<Self> {
+		let mut r = Registry::new();
+		r.register_basic()
+			.register_minimax::<DefaultHeuristic>("default")
+			.register_features();
+		r
+	}
+}
+
+impl Registered for Oware {
+	fn registry() -> Registry<Self> {
+		let mut r = Registry::new();
+		r.register_basic()
+			.register_minimax::<DefaultHeuristic>("default");
+		r
+	}
+}
+
+impl Registered for UltimateTictactoe {
+	fn registry() -> Registry<Self> {
+		let mut r = Registry::new();
+		r.register_basic()
+			.register_minimax::<DefaultHeuristic>("default");
+		r
+	}
+}
+
+impl<const N: usize> Registered for Go<N> {
+	fn registry() -> Registry<Self> {
+		let mut r = Registry::new();
+		r.register_basic()
+			.register_minimax::<DefaultHeuristic>("default");
+		r
+	}
+}
+
+impl<const N: usize> Registered for Hex<N> {
+	fn registry() -> Registry<Self> {
+		let mut r = Registry::new();
+		r.register_basic()
+			.register_minimax::<DefaultHeuristic>("default");
+		r
+	}
+}
+
+impl Registered for Breakthrough {
+	fn registry() -> Registry<Self> {
+		let mut r = Registry::new();
+		r.register_basic()
+			.register_minimax::<DefaultHeuristic>("default");
+		r
+	}
+}
+
+impl Registered for Amazons {
+	fn registry() -> Registry<Self> {
+		let mut r = Registry::new();
+		r.register_basic()
+			.register_minimax::<DefaultHeuristic>("default");
+		r
+	}
+}
+
+impl Registered for Checkers {
+	fn registry() -> Registry<Self> {
+		let mut r = Registry::new();
+		r.register_basic()
+			.register_minimax::<DefaultHeuristic>("default")
+			.register_features();
+		r
+	}
+}
+
+impl<const N: usize> Registered for Nim<N> {
+	fn registry() -> Registry<Self> {
+		let mut r = Registry::new();
+		r.register_basic()
+			.register_minimax::<DefaultHeuristic>("default");
+		r
+	}
+}
+
+impl Registered for Subtraction {
+	fn registry() -> Registry<Self> {
+		let mut r = Registry::new();
+		r.register_basic()
+			.register_minimax::<DefaultHeuristic>("default");
+		r
+	}
+}
+
+impl<const W: usize, const H: usize> Registered for DotsAndBoxes<W, H> {
+	fn registry() -> Registry<Self> {
+		let mut r = Registry::new();
+		r.register_basic()
+			.register_minimax::<DefaultHeuristic>("default");
+		r
+	}
+}
+
+impl Registered for Connect4 {
+	fn registry() -> Registry<Self> {
+		let mut r = Registry::new();
+		r.register_basic()
+			.register_minimax::<DefaultHeuristic>("default");
+		r
+	}
+}
+
+impl Registered for old_tictactoe::Tictactoe {
+	fn registry() -> Registry<Self> {
+		let mut r = Registry::new();
+		r.register_basic()
+			.register_minimax::<DefaultHeuristic>("default");
+		r
+	}
+}
+
+impl<const W: usize, const H: usize, const K: usize> Registered for Mnk<W, H, K> {
+	fn registry() -> Registry<Self> {
+		let mut r = Registry::new();
+		r.register_basic()
+			.register_minimax::<DefaultHeuristic>("default");
+		r
+	}
+}
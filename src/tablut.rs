@@ -1,4 +1,5 @@
 use crate::game::*;
+use crate::tafl::capture;
 /*
 0000000
 00000000000
@@ -143,17 +144,172 @@ pub const CAPTURE_AID: u128 = 0b000000000000000000000010100000000010000000000000
 */
 pub const GOAL: u128 = 0b00000000000000000000110001100010000000100100000001000000000000000000000000000000000001000000010010000000100011000110000000000000;
 
+/*
+0000000
+00000000000
+01111111110
+01000000010
+01000000010
+01000000010
+01000000010
+01000000010
+01000000010
+01000000010
+01111111110
+00000000000
+*/
+pub const EDGE: u128 = 0b00000000000000000001111111110010000000100100000001001000000010010000000100100000001001000000010010000000100111111111000000000000;
+
+/*
+0000000
+00000000000
+01000000010
+00000000000
+00000000000
+00000000000
+00000000000
+00000000000
+00000000000
+00000000000
+01000000010
+00000000000
+*/
+pub const CORNERS: u128 = 0b00000000000000000001000000010000000000000000000000000000000000000000000000000000000000000000000000000000000100000001000000000000;
+
+pub const THRONE: u128 = START_K;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum KingCapture {
+	// on four sides on the throne and next to it (K_SAFETY), by two anywhere else
+	Ashton,
+	// on four sides everywhere, the throne and the camps count as attackers
+	Four,
+	// by two like any other piece
+	Two,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Escape {
+	// GOAL: the edge without the camps and the corners
+	Goal,
+	// any edge square that isn't blocked
+	Edge,
+	Corner,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TablutRules {
+	pub king_capture: KingCapture,
+	// the king takes part in captures
+	pub armed_king: bool,
+	pub escape: Escape,
+	// the camps block moves (except to leave them) and help captures, else only the throne does
+	pub camps: bool,
+	// a position (board and side to move) reached this many times is a draw, 0 never
+	pub repetitions: usize,
+}
+
+pub const ASHTON: TablutRules = TablutRules {
+	king_capture: KingCapture::Ashton,
+	armed_king: true,
+	escape: Escape::Goal,
+	camps: true,
+	repetitions: 2,
+};
+
+pub const SEABATTLE: TablutRules = TablutRules {
+	king_capture: KingCapture::Four,
+	armed_king: true,
+	escape: Escape::Edge,
+	camps: false,
+	repetitions: 3,
+};
+
+// the brandubh king on the tablut board: armed, taken by two away from the throne, escapes on the corners
+pub const CORNER: TablutRules = TablutRules {
+	king_capture: KingCapture::Ashton,
+	armed_king: true,
+	escape: Escape::Corner,
+	camps: false,
+	repetitions: 3,
+};
+
+impl Default for TablutRules {
+	fn default() -> Self {
+		ASHTON
+	}
+}
+
 // a &= !(1u128<<p);
 // a |= 1u128<<p;
 // (a>>p)&1 != 0
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+// (a>>p)&1 != 0, but false for positions that fall off the board
+#[inline(always)]
+fn bit(a: u128, p: u8) -> bool {
+	a.checked_shr(p as u32).is_some_and(|x| x & 1 != 0)
+}
+
+#[derive(Debug, PartialEq, Eq)]
 pub struct Tablut {
 	pub a: u128,
 	pub d: u128,
 	pub k: u128,
 	pub turn: u32,
 	pub state: State,
+	pub rules: &'static TablutRules,
+	// a, d and k of the positions so far, the last one is the current position, the side
+	// to move changes every ply
+	history: Vec<(u128, u128, u128)>,
+	// positions before a capture can't come back, only history[since..] is searched
+	since: usize,
+}
+
+// by hand so that clone_from reuses the history, the searches restore their root with it
+impl Clone for Tablut {
+	fn clone(&self) -> Self {
+		Tablut {
+			history: self.history.clone(),
+			..*self
+		}
+	}
+	fn clone_from(&mut self, source: &Self) {
+		self.history.clone_from(&source.history);
+		(self.a, self.d, self.k, self.turn, self.state, self.rules) = (
+			source.a,
+			source.d,
+			source.k,
+			source.turn,
+			source.state,
+			source.rules,
+		);
+		self.since = source.since;
+	}
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TablutRollback {
+	a: u128,
+	d: u128,
+	k: u128,
+	turn: u32,
+	state: State,
+	len: usize,
+	since: usize,
+}
+
+impl Default for TablutRollback {
+	fn default() -> Self {
+		TablutRollback {
+			a: 0,
+			d: 0,
+			k: 0,
+			turn: 0,
+			state: State::Going,
+			len: 0,
+			since: 0,
+		}
+	}
 }
 
 impl Default for Tablut {
@@ -172,35 +328,136 @@ pub enum Tile {
 impl Tablut {
 	pub fn get_board(&self) -> [[Tile; 9]; 9] {
 		let mut ans = [[Tile::E; 9]; 9];
-		for y in 0..9 {
-			for x in 0..9 {
+		for (y, row) in ans.iter_mut().enumerate() {
+			for (x, tile) in row.iter_mut().enumerate() {
 				let p = y * 11 + x + 12;
 				if (self.a >> p) & 1 != 0 {
-					ans[y][x] = Tile::A;
+					*tile = Tile::A;
 				} else if (self.d >> p) & 1 != 0 {
-					ans[y][x] = Tile::D;
+					*tile = Tile::D;
 				} else if (self.k >> p) & 1 != 0 {
-					ans[y][x] = Tile::K;
+					*tile = Tile::K;
 				}
 			}
 		}
 		ans
 	}
-}
-
-impl Game for Tablut {
-	type M = (u8, u8);
-	type S = Self;
-	type R = Self;
-	fn new(t: bool) -> Self {
-		Tablut {
+	// inverse of get_board, t is true if the defenders are to move
+	pub fn from_board(board: &[[Tile; 9]; 9], t: bool) -> Self {
+		Self::from_board_with_rules(board, &ASHTON, t)
+	}
+	pub fn from_board_with_rules(
+		board: &[[Tile; 9]; 9],
+		rules: &'static TablutRules,
+		t: bool,
+	) -> Self {
+		let mut ans = Tablut {
+			a: 0,
+			d: 0,
+			k: 0,
+			turn: if t { 0 } else { 1 },
+			state: State::Going,
+			rules,
+			history: vec![],
+			since: 0,
+		};
+		for (y, row) in board.iter().enumerate() {
+			for (x, tile) in row.iter().enumerate() {
+				let p = y * 11 + x + 12;
+				match tile {
+					Tile::A => ans.a |= 1u128 << p,
+					Tile::D => ans.d |= 1u128 << p,
+					Tile::K => ans.k |= 1u128 << p,
+					Tile::E => {}
+				}
+			}
+		}
+		ans.state = if ans.k == 0 {
+			State::Lose
+		} else if ans.k & ans.goal() != 0 {
+			State::Win
+		} else {
+			State::Going
+		};
+		ans.history.push((ans.a, ans.d, ans.k));
+		ans
+	}
+	pub fn with_rules(rules: &'static TablutRules, t: bool) -> Self {
+		let mut ans = Tablut {
 			a: START_A,
 			d: START_D,
 			k: START_K,
 			turn: if t { 0 } else { 1 },
 			state: State::Going,
+			rules,
+			history: vec![],
+			since: 0,
+		};
+		ans.history.push((ans.a, ans.d, ans.k));
+		ans
+	}
+	// times the current position has been reached
+	pub fn repetitions(&self) -> usize {
+		let now = (self.a, self.d, self.k);
+		self.history[self.since..]
+			.iter()
+			.rev()
+			.step_by(2)
+			.filter(|x| **x == now)
+			.count()
+	}
+	// whether an earlier position, with the other side to move, is one move of a piece of
+	// the side to move away, so that a move from here can be a repetition
+	pub fn can_repeat(&self) -> bool {
+		let one = |x: u128, y: u128| (x ^ y).count_ones() == 2;
+		self.history[self.since..]
+			.iter()
+			.rev()
+			.skip(1)
+			.step_by(2)
+			.any(|&(a, d, k)| {
+				if self.turn() {
+					a == self.a && ((d == self.d && one(k, self.k)) || (k == self.k && one(d, self.d)))
+				} else {
+					d == self.d && k == self.k && one(a, self.a)
+				}
+			})
+	}
+	// squares nobody can move to or through, BLOCK with the camps
+	pub fn block(&self) -> u128 {
+		if self.rules.camps {
+			BLOCK
+		} else {
+			BLOCK & !(CITADELS_0 | CITADELS_1)
+		}
+	}
+	// squares that capture like a piece of the defenders, or of the attackers: CAPTURE_AID
+	// with the camps, else the throne, for the attackers only while the king is off it
+	pub fn capture_aid(&self, defenders: bool) -> u128 {
+		if self.rules.camps {
+			CAPTURE_AID
+		} else if defenders {
+			THRONE
+		} else {
+			THRONE & !self.k
+		}
+	}
+	pub fn goal(&self) -> u128 {
+		match self.rules.escape {
+			Escape::Goal => GOAL,
+			Escape::Edge => EDGE & !self.block(),
+			Escape::Corner => CORNERS,
 		}
 	}
+}
+
+impl Game for Tablut {
+	type M = (u8, u8);
+	type S = (u128, u128, u128, bool, bool);
+	type R = TablutRollback;
+	fn new(t: bool) -> Self {
+		Self::with_rules(&ASHTON, t)
+	}
 	fn turn(&self) -> bool {
 		self.turn & 1 == 0
 	}
@@ -208,17 +465,19 @@ impl Game for Tablut {
 		let mut ans = Vec::<Self::M>::with_capacity(96);
 		let mut allies = if self.turn() { self.d | self.k } else { self.a };
 		let pawns = self.d | self.k | self.a;
+		let block = self.block();
 		while allies != 0 {
 			let p = allies.trailing_zeros() as u8;
 			allies ^= 1u128 << p;
-			let pass = !(if self.turn() {
-				BLOCK
+			// attackers can walk inside the camp they start in
+			let pass = !(if self.turn() || !self.rules.camps {
+				block
 			} else if (CITADELS_0 >> p) & 1 != 0 {
-				BLOCK ^ CITADELS_0
+				block ^ CITADELS_0
 			} else if (CITADELS_1 >> p) & 1 != 0 {
-				BLOCK ^ CITADELS_1
+				block ^ CITADELS_1
 			} else {
-				BLOCK
+				block
 			} | pawns);
 			let mut i = p + 1;
 			while (pass >> i) & 1 != 0 {
@@ -254,7 +513,7 @@ impl Game for Tablut {
 				[9, 4, 5, 3, 6, 7, 2, 1, 0], // def, lower is better
 				[9, 5, 2, 4, 3, 7, 1, 6, 0], // atk, lower is better
 			];
-			let dif = if m.0 > m.1 { m.0 - m.1 } else { m.1 - m.0 };
+			let dif = m.0.abs_diff(m.1);
 			let dist = if dif >= 11 { dif / 11 } else { dif };
 			if self.turn() {
 				if (self.k >> m.0) & 1 != 0 {
@@ -263,10 +522,10 @@ impl Game for Tablut {
 					ORDI[(self.turn & 1) as usize][dist as usize] + 8
 				}
 			} else {
-				if (self.k >> (m.1 + 1)) & 1 != 0
-					|| (self.k >> (m.1 + 11)) & 1 != 0
-					|| (self.k >> (m.1 - 1)) & 1 != 0
-					|| (self.k >> (m.1 - 11)) & 1 != 0
+				if bit(self.k, m.1 + 1)
+					|| bit(self.k, m.1 + 11)
+					|| bit(self.k, m.1.wrapping_sub(1))
+					|| bit(self.k, m.1.wrapping_sub(11))
 				{
 					ORDI[(self.turn & 1) as usize][dist as usize]
 				} else {
@@ -277,7 +536,10 @@ impl Game for Tablut {
 		ans
 	}
 	fn get_static_state(&self) -> Self::S {
-		*self
+		// what a line is worth changes when its next move can be a repetition draw, the
+		// earlier positions themselves stay out so that transpositions still match
+		let repeat = self.rules.repetitions > 0 && self.can_repeat();
+		(self.a, self.d, self.k, self.turn(), repeat)
 	}
 	fn state(&self) -> State {
 		self.state
@@ -287,9 +549,11 @@ impl Game for Tablut {
 			self.state = if self.turn() { State::Win } else { State::Lose };
 			return;
 		}
+		let pieces = self.a.count_ones() + self.d.count_ones();
 		if self.turn() {
 			// def
-			if (self.d >> m.0) & 1 != 0 {
+			let king = (self.d >> m.0) & 1 == 0;
+			if !king {
 				// d moved
 				self.d &= !(1u128 << m.0);
 				self.d |= 1u128 << m.1;
@@ -298,40 +562,28 @@ impl Game for Tablut {
 				self.k &= !(1u128 << m.0);
 				self.k |= 1u128 << m.1;
 			}
-			let capturers = self.d | self.k | CAPTURE_AID;
-			if (capturers >> (m.1 + 2)) & 1 != 0 {
-				self.a &= !(1u128 << (m.1 + 1));
-			}
-			if (capturers >> (m.1 + 22)) & 1 != 0 {
-				self.a &= !(1u128 << (m.1 + 11));
-			}
-			if (capturers >> (m.1 - 2)) & 1 != 0 {
-				self.a &= !(1u128 << (m.1 - 1));
-			}
-			if (capturers >> (m.1 - 22)) & 1 != 0 {
-				self.a &= !(1u128 << (m.1 - 11));
+			if self.rules.armed_king {
+				let capturers = self.d | self.k | self.capture_aid(true);
+				capture(&mut self.a, capturers, m.1 as usize, 11);
+			} else if !king {
+				let capturers = self.d | self.capture_aid(true);
+				capture(&mut self.a, capturers, m.1 as usize, 11);
 			}
 		} else {
 			// atk
 			self.a &= !(1u128 << m.0);
 			self.a |= 1u128 << m.1;
-			let capturers = self.a | CAPTURE_AID;
-			if (capturers >> (m.1 + 2)) & 1 != 0 {
-				self.d &= !(1u128 << (m.1 + 1));
-			}
-			if (capturers >> (m.1 + 22)) & 1 != 0 {
-				self.d &= !(1u128 << (m.1 + 11));
-			}
-			if (capturers >> (m.1 - 2)) & 1 != 0 {
-				self.d &= !(1u128 << (m.1 - 1));
-			}
-			if (capturers >> (m.1 - 22)) & 1 != 0 {
-				self.d &= !(1u128 << (m.1 - 11));
-			}
+			let capturers = self.a | self.capture_aid(false);
+			capture(&mut self.d, capturers, m.1 as usize, 11);
 			let sides =
 				(1u128 << (m.1 + 1)) | (1u128 << (m.1 + 11)) | (1u128 << (m.1 - 1)) | (1u128 << (m.1 - 11));
 			if self.k & sides != 0 {
-				if self.k & K_SAFETY != 0 {
+				let four = match self.rules.king_capture {
+					KingCapture::Ashton => self.k & K_SAFETY != 0,
+					KingCapture::Four => true,
+					KingCapture::Two => false,
+				};
+				if four {
 					let kp = self.k.trailing_zeros();
 					let ksides =
 						(1u128 << (kp + 1)) | (1u128 << (kp + 11)) | (1u128 << (kp - 1)) | (1u128 << (kp - 11));
@@ -339,37 +591,50 @@ impl Game for Tablut {
 						self.k = 0;
 					}
 				} else {
-					if (capturers >> (m.1 + 2)) & 1 != 0 {
-						self.k &= !(1u128 << (m.1 + 1));
-					}
-					if (capturers >> (m.1 + 22)) & 1 != 0 {
-						self.k &= !(1u128 << (m.1 + 11));
-					}
-					if (capturers >> (m.1 - 2)) & 1 != 0 {
-						self.k &= !(1u128 << (m.1 - 1));
-					}
-					if (capturers >> (m.1 - 22)) & 1 != 0 {
-						self.k &= !(1u128 << (m.1 - 11));
-					}
+					capture(&mut self.k, capturers, m.1 as usize, 11);
 				}
 			}
 		}
 		self.turn += 1;
 		self.state = if self.k == 0 {
 			State::Lose
-		} else if self.k & GOAL != 0 {
+		} else if self.k & self.goal() != 0 {
 			State::Win
 		} else {
 			State::Going
+		};
+		if pieces != self.a.count_ones() + self.d.count_ones() {
+			self.since = self.history.len();
+		}
+		self.history.push((self.a, self.d, self.k));
+		if self.state == State::Going
+			&& self.rules.repetitions > 0
+			&& self.repetitions() >= self.rules.repetitions
+		{
+			self.state = State::Draw;
 		}
 	}
 	fn mov_with_rollback(&mut self, m: &Self::M) -> Self::R {
-		let t = *self;
+		let rb = TablutRollback {
+			a: self.a,
+			d: self.d,
+			k: self.k,
+			turn: self.turn,
+			state: self.state,
+			len: self.history.len(),
+			since: self.since,
+		};
 		self.mov(m);
-		t
+		rb
 	}
-	fn rollback(&mut self, rbf: Self::R) {
-		*self = rbf;
+	fn rollback(&mut self, rb: Self::R) {
+		self.a = rb.a;
+		self.d = rb.d;
+		self.k = rb.k;
+		self.turn = rb.turn;
+		self.state = rb.state;
+		self.history.truncate(rb.len);
+		self.since = rb.since;
 	}
 }
 impl std::fmt::Display for Tablut {
@@ -383,7 +648,7 @@ impl std::fmt::Display for Tablut {
 					write!(f, "D")?;
 				} else if (self.k >> p) & 1 != 0 {
 					write!(f, "K")?;
-				} else if (BLOCK >> p) & 1 != 0 {
+				} else if (self.block() >> p) & 1 != 0 {
 					write!(f, ",")?;
 				} else {
 					write!(f, ".")?;
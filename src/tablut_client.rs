@@ -0,0 +1,191 @@
+// Client for the Tablut university challenge server (Ashton rules).
+// Every message is a json string preceded by its length as a big endian u32.
+// The client sends its name, then the server sends
+//   {"board":[["EMPTY","WHITE","BLACK","KING","THRONE",...],...],"turn":"WHITE"}
+// after every move, with turn one of WHITE, BLACK, WHITEWIN, BLACKWIN, DRAW,
+// and the client answers on its turn with {"from":"e4","to":"f4","turn":"WHITE"}.
+// White are the defenders and move first, row 1 is board[0].
+use crate::ai::Ai;
+use crate::game::*;
+use crate::notation::*;
+use crate::tablut::*;
+use serde_json::json;
+use serde_json::Value;
+use std::io::Error;
+use std::io::ErrorKind;
+use std::io::Read;
+use std::io::Write;
+use std::net::TcpStream;
+use std::net::ToSocketAddrs;
+use std::time::Duration;
+
+pub const WHITE_PORT: u16 = 5800;
+pub const BLACK_PORT: u16 = 5801;
+// left to the network out of the server's time limit
+pub const NETWORK_MARGIN: Duration = Duration::from_millis(500);
+
+fn invalid(msg: String) -> Error {
+	Error::new(ErrorKind::InvalidData, msg)
+}
+
+pub fn read_string<R: Read>(r: &mut R) -> std::io::Result<String> {
+	let mut len = [0u8; 4];
+	r.read_exact(&mut len)?;
+	let mut buf = vec![0u8; u32::from_be_bytes(len) as usize];
+	r.read_exact(&mut buf)?;
+	String::from_utf8(buf).map_err(|e| invalid(e.to_string()))
+}
+
+pub fn write_string<W: Write>(w: &mut W, s: &str) -> std::io::Result<()> {
+	w.write_all(&(s.len() as u32).to_be_bytes())?;
+	w.write_all(s.as_bytes())?;
+	w.flush()
+}
+
+fn turn_name(t: bool) -> &'static str {
+	if t {
+		"WHITE"
+	} else {
+		"BLACK"
+	}
+}
+
+// the board and whose turn it is, or the final state
+pub fn state_from_json(s: &str) -> std::io::Result<(Tablut, Option<bool>)> {
+	let v: Value = serde_json::from_str(s).map_err(|e| invalid(e.to_string()))?;
+	let turn = match v["turn"].as_str() {
+		Some("WHITE") => Some(true),
+		Some("BLACK") => Some(false),
+		Some("WHITEWIN") | Some("BLACKWIN") | Some("DRAW") => None,
+		x => return Err(invalid(format!("bad turn {:?}", x))),
+	};
+	let rows = v["board"]
+		.as_array()
+		.filter(|x| x.len() == 9)
+		.ok_or_else(|| invalid("bad board".to_string()))?;
+	let mut board = [[Tile::E; 9]; 9];
+	for (y, row) in rows.iter().enumerate() {
+		let row = row
+			.as_array()
+			.filter(|x| x.len() == 9)
+			.ok_or_else(|| invalid("bad board row".to_string()))?;
+		for (x, tile) in row.iter().enumerate() {
+			board[y][x] = match tile.as_str() {
+				Some("EMPTY") | Some("THRONE") => Tile::E,
+				Some("WHITE") => Tile::D,
+				Some("BLACK") => Tile::A,
+				Some("KING") => Tile::K,
+				x => return Err(invalid(format!("bad tile {:?}", x))),
+			};
+		}
+	}
+	// the turn of a finished game is not known, it doesn't matter anymore
+	let mut g = Tablut::from_board(&board, turn.unwrap_or(true));
+	g.state = match v["turn"].as_str() {
+		Some("WHITEWIN") => State::Win,
+		Some("BLACKWIN") => State::Lose,
+		Some("DRAW") => State::Draw,
+		_ => g.state,
+	};
+	Ok((g, turn))
+}
+
+pub fn state_to_json(g: &Tablut) -> String {
+	let board: Vec<Vec<&str>> = g
+		.get_board()
+		.iter()
+		.enumerate()
+		.map(|(y, row)| {
+			row
+				.iter()
+				.enumerate()
+				.map(|(x, tile)| match tile {
+					Tile::E if x == 4 && y == 4 => "THRONE",
+					Tile::E => "EMPTY",
+					Tile::A => "BLACK",
+					Tile::D => "WHITE",
+					Tile::K => "KING",
+				})
+				.collect()
+		})
+		.collect();
+	let turn = match g.state() {
+		State::Going => turn_name(g.turn()),
+		State::Win => "WHITEWIN",
+		State::Lose => "BLACKWIN",
+		State::Draw => "DRAW",
+	};
+	json!({ "board": board, "turn": turn }).to_string()
+}
+
+pub fn mov_to_json(m: &<Tablut as Game>::M, t: bool) -> String {
+	json!({
+		"from": square_to_string(m.0 % 11 - 1, m.0 / 11 - 1),
+		"to": square_to_string(m.1 % 11 - 1, m.1 / 11 - 1),
+		"turn": turn_name(t),
+	})
+	.to_string()
+}
+
+pub fn mov_from_json(s: &str) -> std::io::Result<<Tablut as Game>::M> {
+	let v: Value = serde_json::from_str(s).map_err(|e| invalid(e.to_string()))?;
+	let sq = |k: &str| {
+		v[k]
+			.as_str()
+			.and_then(parse_square)
+			.filter(|x| x.0 < 9 && x.1 < 9)
+			.map(|x| x.1 * 11 + x.0 + 12)
+			.ok_or_else(|| invalid(format!("bad {}", k)))
+	};
+	Ok((sq("from")?, sq("to")?))
+}
+
+fn same_position(a: &Tablut, b: &Tablut) -> bool {
+	a.a == b.a && a.d == b.d && a.k == b.k && a.turn() == b.turn()
+}
+
+// plays a whole game on the server, the agent must have been created with Ai::new(true)
+// tl is the server's time limit per move, the final state is from white's point of view
+pub fn play<A: ToSocketAddrs>(
+	addr: A,
+	name: &str,
+	white: bool,
+	agent: &mut dyn Ai<Tablut>,
+	tl: Duration,
+) -> std::io::Result<State> {
+	let mut stream = TcpStream::connect(addr)?;
+	stream.set_nodelay(true)?;
+	write_string(&mut stream, &json!(name).to_string())?;
+	let mut g = Tablut::new(true);
+	loop {
+		let (server, turn) = state_from_json(&read_string(&mut stream)?)?;
+		let turn = match turn {
+			Some(t) => t,
+			None => return Ok(server.state()),
+		};
+		if !same_position(&g, &server) {
+			// the opponent moved, find out how to keep the agent's tree
+			let m = g
+				.get_moves()
+				.into_iter()
+				.find(|m| {
+					let mut h = g.clone();
+					h.mov(m);
+					same_position(&h, &server)
+				})
+				.ok_or_else(|| invalid(format!("can't reach server board from\n{}", g)))?;
+			g.mov(&m);
+			agent.mov(&m);
+		}
+		if turn == white {
+			let m = agent.get_mov(tl.saturating_sub(NETWORK_MARGIN));
+			if m.0 == m.1 {
+				// no moves left, the server will declare the loss
+				continue;
+			}
+			write_string(&mut stream, &mov_to_json(&m, white))?;
+			g.mov(&m);
+			agent.mov(&m);
+		}
+	}
+}
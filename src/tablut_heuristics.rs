@@ -14,8 +14,8 @@ impl Heuristic<Tablut> for BuggedHeuristic {
 				let na = g.a.count_ones() as i64;
 				let mut km = 0i64;
 				let kp = g.k.trailing_zeros();
-				let capturer = g.a | CAPTURE_AID;
-				let pass = !(g.a | g.d | BLOCK);
+				let capturer = g.a | g.capture_aid(false);
+				let pass = !(g.a | g.d | g.block());
 
 				let mut i = kp;
 				while (pass >> i) & 1 != 0 {
@@ -51,6 +51,65 @@ impl Heuristic<Tablut> for BuggedHeuristic {
 	}
 }
 
+// squares the king on p reaches in one move through pass
+fn king_reach(p: u32, pass: u128) -> u128 {
+	let mut ans = 0;
+	for d in [1i32, 11, -1, -11] {
+		let mut i = p as i32 + d;
+		while (pass >> i) & 1 != 0 {
+			ans |= 1u128 << i;
+			i += d;
+		}
+	}
+	ans
+}
+
+// the king's routes to a goal square: one move straight there, and two moves through an
+// empty square that isn't a goal, with every other piece staying where it is
+pub fn victory_paths(g: &Tablut) -> (i64, i64) {
+	let kp = g.k.trailing_zeros();
+	let pass = !(g.a | g.d | g.k | g.block());
+	let goal = g.goal();
+	let one = king_reach(kp, pass);
+	let mut two = 0;
+	let mut via = one & !goal;
+	while via != 0 {
+		let q = via.trailing_zeros();
+		via &= via - 1;
+		two += (king_reach(q, pass | g.k) & goal).count_ones() as i64;
+	}
+	((one & goal).count_ones() as i64, two)
+}
+
+pub const FM_FEATURES: [&str; 5] = [
+	"defenders",
+	"victory_paths",
+	"king_edge",
+	"king_captured_sides",
+	"attackers",
+];
+pub const FM_WEIGHTS: [i64; 5] = [250, 195, 42, -147, -164];
+
+// the terms of FmHeuristic, in the order of FM_FEATURES
+pub fn fm_features(g: &Tablut) -> [i64; 5] {
+	const DIST: [i64; 121] = [
+		6, 6, 6, 6, 6, 6, 6, 6, 6, 6, 6, 6, 1, 0, 0, 1, 2, 1, 0, 0, 1, 6, 6, 0, 1, 1, 2, 3, 2, 1, 1, 0,
+		6, 6, 0, 1, 2, 3, 4, 3, 2, 1, 0, 6, 6, 1, 2, 3, 4, 5, 4, 3, 2, 1, 6, 6, 2, 3, 4, 5, 6, 5, 4, 3,
+		2, 6, 6, 1, 2, 3, 4, 5, 4, 3, 2, 1, 6, 6, 0, 1, 2, 3, 4, 3, 2, 1, 0, 6, 6, 0, 1, 1, 2, 3, 2, 1,
+		1, 0, 6, 6, 1, 0, 0, 1, 2, 1, 0, 0, 1, 6, 6, 6, 6, 6, 6, 6, 6, 6, 6, 6, 6,
+	];
+	let nd = g.d.count_ones() as i64; // number of defender pawns
+	let na = g.a.count_ones() as i64; // number of attacker pawns
+	let kp = g.k.trailing_zeros();
+	let km = 6i64 - DIST[kp as usize]; // 6 - king distance from edge
+	let ks = (1u128 << (kp + 1)) | (1u128 << (kp + 11)) | (1u128 << (kp - 1)) | (1u128 << (kp - 11));
+	let capturers = g.a | g.capture_aid(false);
+	let kcs = (ks & capturers).count_ones() as i64; // king captured sides
+	let (one, two) = victory_paths(g);
+	let vp = one + two; // victory paths
+	[nd, vp, km, kcs, na]
+}
+
 pub struct FmHeuristic;
 impl Heuristic<Tablut> for FmHeuristic {
 	fn eval(g: &Tablut) -> i64 {
@@ -59,24 +118,29 @@ impl Heuristic<Tablut> for FmHeuristic {
 			State::Lose => i64::MIN + g.turn as i64,
 			State::Draw => 0,
 			State::Going => {
-				const DIST: [i64; 121] = [
-					6, 6, 6, 6, 6, 6, 6, 6, 6, 6, 6, 6, 1, 0, 0, 1, 2, 1, 0, 0, 1, 6, 6, 0, 1, 1, 2, 3, 2, 1,
-					1, 0, 6, 6, 0, 1, 2, 3, 4, 3, 2, 1, 0, 6, 6, 1, 2, 3, 4, 5, 4, 3, 2, 1, 6, 6, 2, 3, 4, 5,
-					6, 5, 4, 3, 2, 6, 6, 1, 2, 3, 4, 5, 4, 3, 2, 1, 6, 6, 0, 1, 2, 3, 4, 3, 2, 1, 0, 6, 6, 0,
-					1, 1, 2, 3, 2, 1, 1, 0, 6, 6, 1, 0, 0, 1, 2, 1, 0, 0, 1, 6, 6, 6, 6, 6, 6, 6, 6, 6, 6, 6,
-					6,
-				];
-				let nd = g.d.count_ones() as i64; // number of defender pawns
-				let na = g.a.count_ones() as i64; // number of attacker pawns
-				let kp = g.k.trailing_zeros();
-				let km = 6i64 - DIST[kp as usize] as i64; // 6 - king distance from edge
-				let ks =
-					(1u128 << (kp + 1)) | (1u128 << (kp + 11)) | (1u128 << (kp - 1)) | (1u128 << (kp - 11));
-				let capturers = g.a | CAPTURE_AID;
-				let kcs = (ks & capturers).count_ones() as i64; // king captured sides
-				let vp = 0i64; // victory paths (unimplemented for now)
-				250 * nd + 195 * vp + 42 * km - 147 * kcs - 164 * na
+				let f = fm_features(g);
+				FM_WEIGHTS.iter().zip(f.iter()).map(|(w, f)| w * f).sum()
 			}
 		}
 	}
 }
+
+// FmHeuristic, but the king with a free way to a goal square on its move has as good as
+// escaped, and two of them on the attackers' move are hard to close both
+pub struct VpHeuristic;
+impl Heuristic<Tablut> for VpHeuristic {
+	fn eval(g: &Tablut) -> i64 {
+		if g.state() != State::Going {
+			return FmHeuristic::eval(g);
+		}
+		let (one, _) = victory_paths(g);
+		if g.turn() && one > 0 {
+			// the value of the escape a move later
+			i64::MAX - g.turn as i64 - 1
+		} else if !g.turn() && one > 1 {
+			FmHeuristic::eval(g) + 10000
+		} else {
+			FmHeuristic::eval(g)
+		}
+	}
+}
@@ -0,0 +1,527 @@
+use crate::game::*;
+use std::fmt::Debug;
+use std::hash::Hash;
+use std::marker::PhantomData;
+use std::ops::BitOr;
+
+// Tafl games on boards padded by one square on every side, like Tablut:
+// square (x, y) with 1 <= x, y <= size is at y * stride + x, stride = size + 2,
+// so neighbours are at p +- 1 and p +- stride and a step off the board lands on padding.
+
+// bitboards for the tafl games, u128 up to 9x9, Wide up to 14x14
+pub trait Bits: Copy + Eq + Hash + Debug + Default + BitOr<Output = Self> {
+	const LEN: usize;
+	// false past the end
+	fn get(&self, p: usize) -> bool;
+	fn set(&mut self, p: usize);
+	fn clear(&mut self, p: usize);
+	fn count(&self) -> u32;
+	// removes the lowest square
+	fn pop(&mut self) -> Option<usize>;
+}
+
+impl Bits for u128 {
+	const LEN: usize = 128;
+	#[inline(always)]
+	fn get(&self, p: usize) -> bool {
+		p < 128 && (self >> p) & 1 != 0
+	}
+	#[inline(always)]
+	fn set(&mut self, p: usize) {
+		*self |= 1u128 << p;
+	}
+	#[inline(always)]
+	fn clear(&mut self, p: usize) {
+		*self &= !(1u128 << p);
+	}
+	fn count(&self) -> u32 {
+		self.count_ones()
+	}
+	fn pop(&mut self) -> Option<usize> {
+		if *self == 0 {
+			return None;
+		}
+		let p = self.trailing_zeros() as usize;
+		*self ^= 1u128 << p;
+		Some(p)
+	}
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Wide(pub [u128; 2]);
+
+impl BitOr for Wide {
+	type Output = Self;
+	fn bitor(self, o: Self) -> Self {
+		Wide([self.0[0] | o.0[0], self.0[1] | o.0[1]])
+	}
+}
+
+impl Bits for Wide {
+	const LEN: usize = 256;
+	#[inline(always)]
+	fn get(&self, p: usize) -> bool {
+		p < 256 && (self.0[p >> 7] >> (p & 127)) & 1 != 0
+	}
+	#[inline(always)]
+	fn set(&mut self, p: usize) {
+		self.0[p >> 7] |= 1u128 << (p & 127);
+	}
+	#[inline(always)]
+	fn clear(&mut self, p: usize) {
+		self.0[p >> 7] &= !(1u128 << (p & 127));
+	}
+	fn count(&self) -> u32 {
+		self.0[0].count_ones() + self.0[1].count_ones()
+	}
+	fn pop(&mut self) -> Option<usize> {
+		for (i, x) in self.0.iter_mut().enumerate() {
+			if *x != 0 {
+				let p = x.trailing_zeros() as usize;
+				*x ^= 1u128 << p;
+				return Some(i * 128 + p);
+			}
+		}
+		None
+	}
+}
+
+// custodial capture, shared by every tafl game: the piece that just moved to `to`
+// takes the victims next to it that have a capturer (piece or hostile square) behind them
+#[inline(always)]
+pub fn capture<B: Bits>(victims: &mut B, capturers: B, to: usize, stride: usize) {
+	if capturers.get(to + 2) {
+		victims.clear(to + 1);
+	}
+	if capturers.get(to + 2 * stride) {
+		victims.clear(to + stride);
+	}
+	if capturers.get(to.wrapping_sub(2)) {
+		victims.clear(to - 1);
+	}
+	if capturers.get(to.wrapping_sub(2 * stride)) {
+		victims.clear(to - stride);
+	}
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TaflRules {
+	pub size: u8,
+	// rows from the first one, A attacker, D defender, K king, anything else empty
+	pub start: &'static [&'static str],
+	// the king escapes on the corners, else on any edge square
+	pub corner_escape: bool,
+	// the king takes part in captures
+	pub armed_king: bool,
+	// the king must be surrounded on four sides (the throne counts) and is safe on the edge,
+	// else two attackers take him, like any piece, except on and next to the throne
+	pub strong_king: bool,
+	// a row of pieces on the edge, each with an enemy in front of it,
+	// is taken by closing both its ends (a corner can close one)
+	pub shieldwall: bool,
+	// the defenders win with the king on the edge, able to move, in a fort that can't be broken
+	pub edge_fort: bool,
+}
+
+pub const BRANDUBH: TaflRules = TaflRules {
+	size: 7,
+	start: &[
+		"...A...", //
+		"...A...", "...D...", "AADKDAA", "...D...", "...A...", "...A...",
+	],
+	corner_escape: true,
+	armed_king: true,
+	strong_king: false,
+	shieldwall: false,
+	edge_fort: false,
+};
+
+pub const COPENHAGEN: TaflRules = TaflRules {
+	size: 11,
+	start: &[
+		"...AAAAA...",
+		".....A.....",
+		"...........",
+		"A....D....A",
+		"A...DDD...A",
+		"AA.DDKDD.AA",
+		"A...DDD...A",
+		"A....D....A",
+		"...........",
+		".....A.....",
+		"...AAAAA...",
+	],
+	corner_escape: true,
+	armed_king: true,
+	strong_king: true,
+	shieldwall: true,
+	edge_fort: true,
+};
+
+// a game of the family, with its default rules and a bitboard big enough for them
+pub trait Variant: Copy + Eq + Hash + Debug + 'static {
+	type B: Bits;
+	const RULES: &'static TaflRules;
+	const NAME: &'static str;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct BrandubhVariant;
+impl Variant for BrandubhVariant {
+	type B = u128;
+	const RULES: &'static TaflRules = &BRANDUBH;
+	const NAME: &'static str = "brandubh";
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CopenhagenVariant;
+impl Variant for CopenhagenVariant {
+	type B = Wide;
+	const RULES: &'static TaflRules = &COPENHAGEN;
+	const NAME: &'static str = "copenhagen";
+}
+
+pub type Brandubh = Tafl<BrandubhVariant>;
+pub type Copenhagen = Tafl<CopenhagenVariant>;
+
+// like Tablut, player true are the defenders and win when the king escapes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Tafl<V: Variant> {
+	pub a: V::B,
+	pub d: V::B,
+	pub k: u8, // square of the king, 0 once captured
+	pub turn: u32,
+	pub state: State,
+	pub rules: &'static TaflRules,
+	variant: PhantomData<V>,
+}
+
+impl<V: Variant> Default for Tafl<V> {
+	fn default() -> Self {
+		Tafl::new(true)
+	}
+}
+
+impl<V: Variant> Tafl<V> {
+	pub fn with_rules(rules: &'static TaflRules, t: bool) -> Self {
+		Self::with_position(rules, rules.start, t)
+	}
+	// rows as in TaflRules::start, t is true if the defenders are to move
+	pub fn with_position(rules: &'static TaflRules, rows: &[&str], t: bool) -> Self {
+		let stride = rules.size as usize + 2;
+		assert!(stride * stride <= V::B::LEN.min(256), "board too big");
+		let mut ans: Self = Tafl {
+			a: V::B::default(),
+			d: V::B::default(),
+			k: 0,
+			turn: if t { 0 } else { 1 },
+			state: State::Going,
+			rules,
+			variant: PhantomData,
+		};
+		for (y, row) in rows.iter().enumerate() {
+			for (x, c) in row.chars().enumerate() {
+				let p = (y + 1) * stride + x + 1;
+				match c {
+					'A' => ans.a.set(p),
+					'D' => ans.d.set(p),
+					'K' => ans.k = p as u8,
+					_ => {}
+				}
+			}
+		}
+		ans.state = if ans.k == 0 {
+			State::Lose
+		} else if ans.is_goal(ans.k as usize) {
+			State::Win
+		} else {
+			State::Going
+		};
+		ans
+	}
+	pub fn stride(&self) -> usize {
+		self.rules.size as usize + 2
+	}
+	// coordinates from 1 to size on the board
+	pub fn xy(&self, p: usize) -> (usize, usize) {
+		(p % self.stride(), p / self.stride())
+	}
+	pub fn on_board(&self, p: usize) -> bool {
+		let (x, y) = self.xy(p);
+		let n = self.rules.size as usize;
+		(1..=n).contains(&x) && (1..=n).contains(&y)
+	}
+	pub fn is_edge(&self, p: usize) -> bool {
+		let (x, y) = self.xy(p);
+		let n = self.rules.size as usize;
+		x == 1 || y == 1 || x == n || y == n
+	}
+	pub fn is_corner(&self, p: usize) -> bool {
+		let (x, y) = self.xy(p);
+		let n = self.rules.size as usize;
+		(x == 1 || x == n) && (y == 1 || y == n)
+	}
+	pub fn throne(&self) -> usize {
+		let c = (self.rules.size as usize).div_ceil(2);
+		c * self.stride() + c
+	}
+	pub fn is_goal(&self, p: usize) -> bool {
+		if self.rules.corner_escape {
+			self.is_corner(p)
+		} else {
+			self.is_edge(p)
+		}
+	}
+	fn corners(&self) -> [usize; 4] {
+		let s = self.stride();
+		let n = self.rules.size as usize;
+		[s + 1, s + n, n * s + 1, n * s + n]
+	}
+	fn occupied(&self, p: usize) -> bool {
+		self.a.get(p) || self.d.get(p) || self.k as usize == p
+	}
+	// pieces and hostile squares that take pieces of the other side
+	fn capturers(&self, defenders: bool) -> V::B {
+		let mut ans = if defenders { self.d } else { self.a };
+		if defenders && self.rules.armed_king && self.k != 0 {
+			ans.set(self.k as usize);
+		}
+		for c in self.corners() {
+			ans.set(c);
+		}
+		// the empty throne is hostile to everyone, the occupied one only to the attackers
+		if defenders || self.k as usize != self.throne() {
+			ans.set(self.throne());
+		}
+		ans
+	}
+	fn shieldwall(&self, victims: &mut V::B, capturers: V::B, to: usize, king_in_row: bool) {
+		let (x, y) = self.xy(to);
+		let n = self.rules.size as usize;
+		let s = self.stride() as isize;
+		let (inward, along) = if y == 1 {
+			(s, 1)
+		} else if y == n {
+			(-s, 1)
+		} else if x == 1 {
+			(1, s)
+		} else if x == n {
+			(-1, s)
+		} else {
+			return;
+		};
+		for dir in [along, -along] {
+			let mut i = to.wrapping_add_signed(dir);
+			let mut len = 0;
+			while self.on_board(i)
+				&& (victims.get(i) || (king_in_row && self.k as usize == i))
+				&& capturers.get(i.wrapping_add_signed(inward))
+			{
+				len += 1;
+				i = i.wrapping_add_signed(dir);
+			}
+			if len >= 2 && capturers.get(i) {
+				let mut j = to.wrapping_add_signed(dir);
+				while j != i {
+					victims.clear(j);
+					j = j.wrapping_add_signed(dir);
+				}
+			}
+		}
+	}
+	// a sufficient condition for an edge fort: the squares the king can reach hold no attackers,
+	// and every defender around them has, on both axes, a neighbour that attackers can't stand
+	// on and that isn't hostile to defenders like the corners and the empty throne
+	pub fn edge_fort(&self) -> bool {
+		let kp = self.k as usize;
+		if kp == 0 || !self.is_edge(kp) {
+			return false;
+		}
+		let s = self.stride();
+		let mut inside = vec![false; s * s];
+		let mut walls = vec![];
+		let mut stack = vec![kp];
+		inside[kp] = true;
+		while let Some(p) = stack.pop() {
+			for q in [p + 1, p + s, p - 1, p - s] {
+				if !self.on_board(q) || inside[q] {
+					continue;
+				}
+				if self.a.get(q) {
+					return false;
+				}
+				if self.d.get(q) {
+					if !walls.contains(&q) {
+						walls.push(q);
+					}
+				} else {
+					inside[q] = true;
+					stack.push(q);
+				}
+			}
+		}
+		if !(inside[kp + 1] || inside[kp + s] || inside[kp - 1] || inside[kp - s]) {
+			// the king can't move
+			return false;
+		}
+		let hostile = self.capturers(false);
+		let safe =
+			|q: usize| !self.on_board(q) || ((inside[q] || walls.contains(&q)) && !hostile.get(q));
+		walls
+			.iter()
+			.all(|&w| (safe(w + 1) || safe(w - 1)) && (safe(w + s) || safe(w - s)))
+	}
+}
+
+impl<V: Variant> Game for Tafl<V> {
+	type M = (u8, u8);
+	type S = (V::B, V::B, u8, bool);
+	type R = Self;
+	fn new(t: bool) -> Self {
+		Self::with_rules(V::RULES, t)
+	}
+	fn turn(&self) -> bool {
+		self.turn & 1 == 0
+	}
+	fn get_moves(&self) -> Vec<Self::M> {
+		let mut ans = Vec::with_capacity(128);
+		let mut allies = if self.turn() { self.d } else { self.a };
+		if self.turn() && self.k != 0 {
+			allies.set(self.k as usize);
+		}
+		let s = self.stride() as isize;
+		let throne = self.throne();
+		while let Some(p) = allies.pop() {
+			let king = p == self.k as usize;
+			for dir in [1, s, -1, -s] {
+				let mut i = p.wrapping_add_signed(dir);
+				while self.on_board(i) && !self.occupied(i) {
+					// only the king stops on the throne and the corners, anyone passes the empty throne
+					if king || (i != throne && !self.is_corner(i)) {
+						ans.push((p as u8, i as u8));
+					}
+					i = i.wrapping_add_signed(dir);
+				}
+			}
+		}
+		if ans.is_empty() {
+			ans.push((0, 0));
+		}
+		ans
+	}
+	fn get_moves_sorted(&self) -> Vec<Self::M> {
+		let mut ans = self.get_moves();
+		let s = self.stride();
+		let k = self.k as usize;
+		if self.turn() {
+			ans.sort_by_key(|m| m.0 as usize != k);
+		} else {
+			ans.sort_by_key(|m| {
+				let to = m.1 as usize;
+				!(to + 1 == k || to + s == k || to == k + 1 || to == k + s)
+			});
+		}
+		ans
+	}
+	fn get_static_state(&self) -> Self::S {
+		(self.a, self.d, self.k, self.turn())
+	}
+	fn state(&self) -> State {
+		self.state
+	}
+	fn mov(&mut self, m: &Self::M) {
+		if m.0 == 0 {
+			// no moves left
+			self.state = if self.turn() { State::Lose } else { State::Win };
+			return;
+		}
+		let (from, to) = (m.0 as usize, m.1 as usize);
+		let s = self.stride();
+		if self.turn() {
+			let king = from == self.k as usize;
+			if king {
+				self.k = to as u8;
+			} else {
+				self.d.clear(from);
+				self.d.set(to);
+			}
+			if !king || self.rules.armed_king {
+				let capturers = self.capturers(true);
+				capture(&mut self.a, capturers, to, s);
+				if self.rules.shieldwall {
+					let mut a = self.a;
+					self.shieldwall(&mut a, capturers, to, false);
+					self.a = a;
+				}
+			}
+			if self.is_goal(self.k as usize) || (self.rules.edge_fort && self.edge_fort()) {
+				self.state = State::Win;
+			}
+		} else {
+			self.a.clear(from);
+			self.a.set(to);
+			let capturers = self.capturers(false);
+			capture(&mut self.d, capturers, to, s);
+			let k = self.k as usize;
+			if k == to + 1 || k == to + s || k + 1 == to || k + s == to {
+				let mut hostile = self.a;
+				hostile.set(self.throne());
+				let surrounded = [k + 1, k + s, k - 1, k - s].iter().all(|p| hostile.get(*p));
+				let near_throne = [k, k + 1, k + s, k - 1, k - s].contains(&self.throne());
+				if self.rules.strong_king {
+					if surrounded && !self.is_edge(k) {
+						self.k = 0;
+					}
+				} else if near_throne {
+					if surrounded {
+						self.k = 0;
+					}
+				} else if capturers.get(2 * k - to) {
+					self.k = 0;
+				}
+			}
+			if self.rules.shieldwall {
+				let mut d = self.d;
+				self.shieldwall(&mut d, capturers, to, true);
+				self.d = d;
+			}
+			if self.k == 0 {
+				self.state = State::Lose;
+			}
+		}
+		self.turn += 1;
+	}
+	fn mov_with_rollback(&mut self, m: &Self::M) -> Self::R {
+		let t = *self;
+		self.mov(m);
+		t
+	}
+	fn rollback(&mut self, rbf: Self::R) {
+		*self = rbf;
+	}
+}
+
+impl<V: Variant> std::fmt::Display for Tafl<V> {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		let n = self.rules.size as usize;
+		let s = self.stride();
+		for y in 1..=n {
+			for x in 1..=n {
+				let p = y * s + x;
+				if self.a.get(p) {
+					write!(f, "A")?;
+				} else if self.d.get(p) {
+					write!(f, "D")?;
+				} else if self.k as usize == p {
+					write!(f, "K")?;
+				} else if p == self.throne() || self.is_corner(p) {
+					write!(f, ",")?;
+				} else {
+					write!(f, ".")?;
+				}
+			}
+			writeln!(f)?;
+		}
+		Ok(())
+	}
+}
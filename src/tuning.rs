@@ -0,0 +1,241 @@
+// Texel style tuning of the weights of a FeatureHeuristic: positions from self-play are
+// labelled with how their game ended, 1 if player true won, 0 if it lost, 0.5 for a
+// draw, and the weights are fitted to minimize the logistic loss of sigmoid(eval / scale)
+// against the labels. The scale is fitted first, on the starting weights, so the evals
+// stay in the same units; then gradient descent on real weights, then a local search on
+// the integer ones, as in the original Texel method.
+use crate::feature_heuristic::*;
+use crate::game::*;
+use crate::heuristic::Heuristic;
+use crate::registry::Registered;
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
+use rand_xoshiro::Xoroshiro128Plus;
+use std::time::Duration;
+
+// player true's result
+pub fn label(s: State) -> f64 {
+	match s {
+		State::Win => 1.0,
+		State::Lose => 0.0,
+		_ => 0.5,
+	}
+}
+
+// games between the agents a and b of the registry, each game after random_plies random
+// moves, a plays first in even games and b in odd ones. All the going positions the
+// agents reached are returned with the result of their game.
+// None if one of the names is not registered
+pub fn self_play<G: Registered>(
+	a: &str,
+	b: &str,
+	games: usize,
+	random_plies: usize,
+	tl: Duration,
+	seed: u64,
+) -> Option<Vec<(G, f64)>> {
+	let registry = G::registry();
+	let mut rng = Xoroshiro128Plus::seed_from_u64(seed);
+	let mut ans = vec![];
+	for i in 0..games {
+		let mut players = [registry.create(a, true)?, registry.create(b, true)?];
+		if i % 2 == 1 {
+			players.swap(0, 1);
+		}
+		let mut g = G::new(true);
+		let mut positions = vec![];
+		let mut ply = 0;
+		while g.state() == State::Going {
+			let m = if ply < random_plies {
+				*g.get_moves().choose(&mut rng).unwrap()
+			} else {
+				positions.push(g.clone());
+				players[if g.turn() { 0 } else { 1 }].get_mov(tl)
+			};
+			for p in players.iter_mut() {
+				p.mov(&m);
+			}
+			g.mov(&m);
+			ply += 1;
+		}
+		let r = label(g.state());
+		ans.extend(positions.into_iter().map(|g| (g, r)));
+	}
+	Some(ans)
+}
+
+fn sigmoid(x: f64) -> f64 {
+	1.0 / (1.0 + (-x).exp())
+}
+
+// mean logistic loss of the evals against the results
+fn log_loss(evals: impl Iterator<Item = (f64, f64)>, scale: f64) -> f64 {
+	let mut total = 0.0;
+	let mut n = 0;
+	for (e, r) in evals {
+		let p = sigmoid(e / scale).clamp(1e-9, 1.0 - 1e-9);
+		total -= r * p.ln() + (1.0 - r) * (1.0 - p).ln();
+		n += 1;
+	}
+	total / n.max(1) as f64
+}
+
+pub fn loss<G: Game, H: Heuristic<G>>(data: &[(G, f64)], scale: f64) -> f64 {
+	log_loss(data.iter().map(|(g, r)| (H::eval(g) as f64, *r)), scale)
+}
+
+// the scale that fits H best, to a few percent
+pub fn fit_scale<G: Game, H: Heuristic<G>>(data: &[(G, f64)]) -> f64 {
+	let evals: Vec<(f64, f64)> = data.iter().map(|(g, r)| (H::eval(g) as f64, *r)).collect();
+	let l = |scale: f64| log_loss(evals.iter().copied(), scale);
+	let mut best = 1.0;
+	let mut scale = 1.0;
+	while scale < 1e9 {
+		scale *= 2.0;
+		if l(scale) < l(best) {
+			best = scale;
+		}
+	}
+	let mut step = 1.5;
+	while step > 1.01 {
+		for s in [best * step, best / step] {
+			if l(s) < l(best) {
+				best = s;
+			}
+		}
+		step = step.sqrt();
+	}
+	best
+}
+
+fn feature_loss(data: &[(Vec<i64>, f64)], w: &[f64], scale: f64) -> f64 {
+	log_loss(
+		data.iter().map(|(f, r)| {
+			let e: f64 = w.iter().zip(f.iter()).map(|(w, f)| w * *f as f64).sum();
+			(e, *r)
+		}),
+		scale,
+	)
+}
+
+// steps of gradient descent, each coordinate scaled by a bound on the second derivative
+// of the loss along it; the step is halved whenever the loss goes up
+pub fn gradient_descent(
+	data: &[(Vec<i64>, f64)],
+	mut w: Vec<f64>,
+	scale: f64,
+	steps: usize,
+) -> Vec<f64> {
+	let n = data.len().max(1) as f64;
+	let curvature: Vec<f64> = (0..w.len())
+		.map(|i| {
+			let sq: f64 = data.iter().map(|(f, _)| (f[i] * f[i]) as f64).sum();
+			(sq / n / (4.0 * scale * scale)).max(1e-12)
+		})
+		.collect();
+	let mut rate = 1.0 / w.len() as f64;
+	let mut best = feature_loss(data, &w, scale);
+	for _ in 0..steps {
+		let mut grad = vec![0.0; w.len()];
+		for (f, r) in data {
+			let e: f64 = w.iter().zip(f.iter()).map(|(w, f)| w * *f as f64).sum();
+			let d = sigmoid(e / scale) - r;
+			for (g, f) in grad.iter_mut().zip(f.iter()) {
+				*g += d * *f as f64 / scale / n;
+			}
+		}
+		let next: Vec<f64> = w
+			.iter()
+			.zip(grad.iter().zip(curvature.iter()))
+			.map(|(w, (g, c))| w - rate * g / c)
+			.collect();
+		let l = feature_loss(data, &next, scale);
+		if l < best {
+			best = l;
+			w = next;
+		} else {
+			rate /= 2.0;
+		}
+	}
+	w
+}
+
+// moves one weight at a time by step while that lowers the loss, then halves the step
+pub fn local_search(
+	data: &[(Vec<i64>, f64)],
+	mut w: Vec<i64>,
+	scale: f64,
+	mut step: i64,
+) -> Vec<i64> {
+	let to_f = |w: &[i64]| w.iter().map(|x| *x as f64).collect::<Vec<f64>>();
+	let mut best = feature_loss(data, &to_f(&w), scale);
+	while step > 0 {
+		let mut better = false;
+		for i in 0..w.len() {
+			for d in [step, -step] {
+				w[i] += d;
+				let l = feature_loss(data, &to_f(&w), scale);
+				if l < best {
+					best = l;
+					better = true;
+					break;
+				}
+				w[i] -= d;
+			}
+		}
+		if !better {
+			step /= 2;
+		}
+	}
+	w
+}
+
+// tunes the weights of slot SLOT on data, starting from its current ones, and sets them
+// if the loss went down. Returns the scale and the loss before and after
+pub fn tune<G: Features, const SLOT: usize>(data: &[(G, f64)], steps: usize) -> (f64, f64, f64) {
+	let scale = fit_scale::<G, FeatureHeuristic<SLOT>>(data);
+	let start = weights::<G>(SLOT);
+	let features: Vec<(Vec<i64>, f64)> = data
+		.iter()
+		.map(|(g, r)| (g.features().as_ref().to_vec(), *r))
+		.collect();
+	let before = loss::<G, FeatureHeuristic<SLOT>>(data, scale);
+	let w = gradient_descent(
+		&features,
+		start.iter().map(|x| *x as f64).collect(),
+		scale,
+		steps,
+	);
+	let w: Vec<i64> = w.iter().map(|x| x.round() as i64).collect();
+	let step = w.iter().map(|x| x.abs()).max().unwrap_or(0) / 8 + 1;
+	let w = local_search(&features, w, scale, step);
+	let after = feature_loss(
+		&features,
+		&w.iter().map(|x| *x as f64).collect::<Vec<f64>>(),
+		scale,
+	);
+	if after < before {
+		set_weights::<G>(SLOT, w);
+		(scale, before, after)
+	} else {
+		(scale, before, before)
+	}
+}
+
+// the whole thing for slot 0, the one of the registry's "feature" agents: self-play
+// between agent and itself, tuning, and the weights written to path
+pub fn tune_self_play<G: Registered + Features>(
+	agent: &str,
+	games: usize,
+	random_plies: usize,
+	tl: Duration,
+	path: &str,
+) -> Result<(), String> {
+	let data = self_play::<G>(agent, agent, games, random_plies, tl, 0)
+		.ok_or(format!("unknown agent {}", agent))?;
+	eprintln!("{} positions from {} games", data.len(), games);
+	let (scale, before, after) = tune::<G, 0>(&data, 1000);
+	eprintln!("scale {} loss {} -> {}", scale, before, after);
+	eprint!("{}", write_weights::<G>(&weights::<G>(0)));
+	save_weights::<G>(0, path)
+}
@@ -0,0 +1,181 @@
+// Ultimate Tic-Tac-Toe: nine tictactoe boards in a 3x3 meta board. A move on square c
+// of a board sends the other player to board c, a player sent to a board that is won
+// or full can play anywhere. Three boards in a line win, all boards closed is a draw.
+// Square i of board b is at b * 9 + i, boards and squares go row by row.
+use crate::game::*;
+
+pub const LINES: [u16; 8] = [
+	0b000000111,
+	0b000111000,
+	0b111000000,
+	0b001001001,
+	0b010010010,
+	0b100100100,
+	0b100010001,
+	0b001010100,
+];
+// anywhere
+pub const FREE: u8 = 9;
+
+pub fn won(b: u16) -> bool {
+	LINES.iter().any(|l| b & l == *l)
+}
+
+// the 9 squares of board i
+fn board(b: u128, i: u8) -> u16 {
+	((b >> (i * 9)) & 0x1ff) as u16
+}
+
+#[derive(Debug, Hash, Clone, Copy, PartialEq, Eq)]
+pub struct UltimateTictactoe {
+	// squares of player true and of player false
+	pub x: u128,
+	pub o: u128,
+	// boards won by player true and by player false
+	pub meta: (u16, u16),
+	// boards won or full
+	closed: u16,
+	// the board to play on, FREE for any
+	next: u8,
+	turn: bool,
+	state: State,
+}
+
+impl UltimateTictactoe {
+	pub fn next(&self) -> u8 {
+		self.next
+	}
+	// squares of board i of player true and of player false
+	pub fn board(&self, i: u8) -> (u16, u16) {
+		(board(self.x, i), board(self.o, i))
+	}
+	pub fn closed(&self) -> u16 {
+		self.closed
+	}
+	// updates the meta board for board i and the state
+	fn close(&mut self, i: u8) {
+		let (x, o) = self.board(i);
+		let bit = 1u16 << i;
+		self.meta.0 &= !bit;
+		self.meta.1 &= !bit;
+		self.closed &= !bit;
+		if won(x) {
+			self.meta.0 |= bit;
+		} else if won(o) {
+			self.meta.1 |= bit;
+		}
+		if self.meta.0 & bit != 0 || self.meta.1 & bit != 0 || x | o == 0x1ff {
+			self.closed |= bit;
+		}
+		self.state = if won(self.meta.0) {
+			State::Win
+		} else if won(self.meta.1) {
+			State::Lose
+		} else if self.closed == 0x1ff {
+			State::Draw
+		} else {
+			State::Going
+		};
+	}
+}
+
+impl Game for UltimateTictactoe {
+	type M = u8; // board * 9 + square
+	type S = (u128, u128, u8, bool);
+	type R = (u8, u8); // the move and the previous next
+	fn new(t: bool) -> Self {
+		UltimateTictactoe {
+			x: 0,
+			o: 0,
+			meta: (0, 0),
+			closed: 0,
+			next: FREE,
+			turn: t,
+			state: State::Going,
+		}
+	}
+	fn turn(&self) -> bool {
+		self.turn
+	}
+	fn get_moves(&self) -> Vec<u8> {
+		let boards = if self.next == FREE {
+			0..9
+		} else {
+			self.next..self.next + 1
+		};
+		let mut ans = vec![];
+		for i in boards {
+			if self.closed & (1 << i) != 0 {
+				continue;
+			}
+			let (x, o) = self.board(i);
+			let mut e = !(x | o) & 0x1ff;
+			while e != 0 {
+				ans.push(i * 9 + e.trailing_zeros() as u8);
+				e &= e - 1;
+			}
+		}
+		ans
+	}
+	fn get_moves_sorted(&self) -> Vec<u8> {
+		self.get_moves()
+	}
+	fn get_static_state(&self) -> Self::S {
+		(self.x, self.o, self.next, self.turn)
+	}
+	fn state(&self) -> State {
+		self.state
+	}
+	fn mov(&mut self, m: &u8) {
+		if self.turn {
+			self.x |= 1u128 << m;
+		} else {
+			self.o |= 1u128 << m;
+		}
+		self.close(m / 9);
+		let c = m % 9;
+		self.next = if self.closed & (1 << c) != 0 { FREE } else { c };
+		self.turn = !self.turn;
+	}
+	fn mov_with_rollback(&mut self, m: &u8) -> Self::R {
+		let rb = (*m, self.next);
+		self.mov(m);
+		rb
+	}
+	fn rollback(&mut self, (m, next): Self::R) {
+		self.x &= !(1u128 << m);
+		self.o &= !(1u128 << m);
+		self.close(m / 9);
+		self.next = next;
+		self.turn = !self.turn;
+	}
+}
+
+impl std::fmt::Display for UltimateTictactoe {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		for y in 0..9 {
+			if y == 3 || y == 6 {
+				writeln!(f, "---+---+---")?;
+			}
+			for x in 0..9 {
+				if x == 3 || x == 6 {
+					write!(f, "|")?;
+				}
+				let m = (y / 3 * 3 + x / 3) * 9 + y % 3 * 3 + x % 3;
+				write!(
+					f,
+					"{}",
+					if (self.x >> m) & 1 != 0 {
+						'X'
+					} else if (self.o >> m) & 1 != 0 {
+						'O'
+					} else {
+						'.'
+					}
+				)?;
+			}
+			writeln!(f)?;
+		}
+		Ok(())
+	}
+}